@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+pub const NUMERALS: [(&str, u8); 10] = [
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+];
+
+pub const ENGLISH_WORDS: [(&str, u8); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+#[derive(Default)]
+struct TrieNode {
+    children: HashMap<u8, usize>,
+    value: Option<u8>,
+}
+
+// Byte-wise trie, so any UTF-8 vocabulary works without caring about char boundaries
+#[derive(Default)]
+struct Trie {
+    nodes: Vec<TrieNode>,
+}
+
+impl Trie {
+    fn insert(&mut self, word: impl Iterator<Item = u8>, value: u8) {
+        if self.nodes.is_empty() {
+            self.nodes.push(TrieNode::default());
+        }
+
+        let mut curr = 0;
+
+        for byte in word {
+            curr = match self.nodes[curr].children.get(&byte) {
+                Some(&next) => next,
+                None => {
+                    self.nodes.push(TrieNode::default());
+                    let next = self.nodes.len() - 1;
+                    self.nodes[curr].children.insert(byte, next);
+                    next
+                }
+            };
+        }
+
+        self.nodes[curr].value = Some(value);
+    }
+
+    // The value of the shortest word that `bytes` starts with, if any
+    fn match_prefix<'a>(&self, bytes: impl Iterator<Item = &'a u8>) -> Option<u8> {
+        let mut curr = 0;
+
+        for byte in bytes {
+            curr = *self.nodes.get(curr)?.children.get(byte)?;
+
+            if let Some(value) = self.nodes[curr].value {
+                return Some(value);
+            }
+        }
+
+        None
+    }
+}
+
+// Finds the first and last digit token in a line. Forward scans look words up as written
+// and backward scans look them up reversed, so overlapping words like `twone` give 2 and 1.
+pub struct DigitExtractor {
+    forward: Trie,
+    backward: Trie,
+}
+
+impl DigitExtractor {
+    pub fn new<'a>(vocabulary: impl IntoIterator<Item = &'a (&'a str, u8)>) -> Self {
+        let mut forward = Trie::default();
+        let mut backward = Trie::default();
+
+        for &(word, value) in vocabulary {
+            forward.insert(word.bytes(), value);
+            backward.insert(word.bytes().rev(), value);
+        }
+
+        DigitExtractor { forward, backward }
+    }
+
+    pub fn first(&self, line: &str) -> Option<u8> {
+        let bytes = line.as_bytes();
+
+        (0..bytes.len()).find_map(|start| self.forward.match_prefix(bytes[start..].iter()))
+    }
+
+    pub fn last(&self, line: &str) -> Option<u8> {
+        let bytes = line.as_bytes();
+
+        (1..=bytes.len())
+            .rev()
+            .find_map(|end| self.backward.match_prefix(bytes[..end].iter().rev()))
+    }
+
+    pub fn calibration_value(&self, line: &str) -> Option<u32> {
+        Some(self.first(line)? as u32 * 10 + self.last(line)? as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::extractor::{DigitExtractor, ENGLISH_WORDS, NUMERALS};
+
+    #[test]
+    fn numerals_only() {
+        let extractor = DigitExtractor::new(NUMERALS.iter());
+
+        assert_eq!(extractor.calibration_value("1abc2"), Some(12));
+        assert_eq!(extractor.calibration_value("a1b2c3d4e5f"), Some(15));
+        assert_eq!(extractor.calibration_value("treb7uchet"), Some(77));
+        assert_eq!(extractor.calibration_value("two1nine"), Some(11));
+        assert_eq!(extractor.calibration_value("trebuchet"), None);
+        assert_eq!(extractor.calibration_value(""), None);
+    }
+
+    #[test]
+    fn spelled_words_overlap() {
+        let extractor = DigitExtractor::new(NUMERALS.iter().chain(ENGLISH_WORDS.iter()));
+
+        for (line, expected) in [
+            ("two1nine", 29),
+            ("eightwothree", 83),
+            ("abcone2threexyz", 13),
+            ("xtwone3four", 24),
+            ("4nineeightseven2", 42),
+            ("zoneight234", 14),
+            ("7pqrstsixteen", 76),
+            ("twone", 21),
+            ("oneight", 18),
+        ] {
+            assert_eq!(
+                extractor.calibration_value(line),
+                Some(expected),
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn pluggable_vocabulary() {
+        let german = [
+            ("null", 0),
+            ("eins", 1),
+            ("zwei", 2),
+            ("drei", 3),
+            ("vier", 4),
+            ("fünf", 5),
+        ];
+        let extractor = DigitExtractor::new(german.iter());
+
+        assert_eq!(extractor.calibration_value("xnullzweiy"), Some(2));
+        assert_eq!(extractor.calibration_value("fünfdreins"), Some(51));
+        assert_eq!(extractor.calibration_value("one two"), None);
+    }
+}
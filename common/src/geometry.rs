@@ -0,0 +1,634 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::{Add, Mul, Neg, Sub};
+
+// Any primitive integer can be used as a coordinate. Intermediate products (cross
+// products, shoelace sums) are widened to i128 so narrow or unsigned coordinates can't
+// overflow halfway through a calculation; u128 coordinates past i128::MAX don't widen
+// faithfully.
+pub trait Coord:
+    Copy + Ord + Hash + Debug + Default + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    fn one() -> Self;
+
+    fn widen(self) -> i128;
+
+    // `None` when the value doesn't fit in this coordinate type
+    fn narrow(value: i128) -> Option<Self>;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    fn checked_sub(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_coord {
+    ($($t:ty),*) => {
+        $(
+            impl Coord for $t {
+                fn one() -> Self {
+                    1
+                }
+
+                fn widen(self) -> i128 {
+                    self as i128
+                }
+
+                fn narrow(value: i128) -> Option<Self> {
+                    <$t>::try_from(value).ok()
+                }
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_coord!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+fn abs_diff<T: Coord>(a: T, b: T) -> T {
+    if a >= b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+// Headings on a grid, where x counts rows downwards and y counts columns to the right
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Copy, Clone)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    // Clockwise, starting from Up
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub fn turn_left(self) -> Self {
+        self.turn_right().turn_right().turn_right()
+    }
+
+    pub fn opposite(self) -> Self {
+        self.turn_right().turn_right()
+    }
+
+    pub fn unit_vec<T: Coord + Neg<Output = T>>(self) -> Vec2<T> {
+        let one = T::one();
+        let zero = T::default();
+
+        match self {
+            Direction::Up => Vec2::new(-one, zero),
+            Direction::Right => Vec2::new(zero, one),
+            Direction::Down => Vec2::new(one, zero),
+            Direction::Left => Vec2::new(zero, -one),
+        }
+    }
+}
+
+#[derive(Debug, Default, Ord, PartialOrd, Eq, PartialEq, Hash, Copy, Clone)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Coord> Point2<T> {
+    pub fn new(x: T, y: T) -> Self {
+        Point2 { x, y }
+    }
+
+    pub fn manhattan(&self, other: &Point2<T>) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y)
+    }
+
+    pub fn chebyshev(&self, other: &Point2<T>) -> T {
+        abs_diff(self.x, other.x).max(abs_diff(self.y, other.y))
+    }
+
+    // The point `distance` steps away, or None if that falls outside the range of T. This
+    // keeps unsigned grid coordinates from wrapping below zero.
+    pub fn step(&self, direction: Direction, distance: T) -> Option<Point2<T>> {
+        match direction {
+            Direction::Up => Some(Point2::new(self.x.checked_sub(distance)?, self.y)),
+            Direction::Right => Some(Point2::new(self.x, self.y.checked_add(distance)?)),
+            Direction::Down => Some(Point2::new(self.x.checked_add(distance)?, self.y)),
+            Direction::Left => Some(Point2::new(self.x, self.y.checked_sub(distance)?)),
+        }
+    }
+
+    // The orthogonal neighbours that exist in T, in the order of `Direction::ALL`
+    pub fn neighbours(&self) -> impl Iterator<Item = (Direction, Point2<T>)> {
+        let point = *self;
+
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| Some((direction, point.step(direction, T::one())?)))
+    }
+
+    // Neighbours that also fall inside a grid of `rows` x `cols` cells starting at zero
+    pub fn grid_neighbours(
+        &self,
+        rows: T,
+        cols: T,
+    ) -> impl Iterator<Item = (Direction, Point2<T>)> {
+        self.neighbours().filter(move |(_, p)| {
+            p.x >= T::default() && p.x < rows && p.y >= T::default() && p.y < cols
+        })
+    }
+}
+
+impl<T: Coord> Add<Vec2<T>> for Point2<T> {
+    type Output = Point2<T>;
+
+    fn add(self, rhs: Vec2<T>) -> Self::Output {
+        Point2::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Coord> Sub<Vec2<T>> for Point2<T> {
+    type Output = Point2<T>;
+
+    fn sub(self, rhs: Vec2<T>) -> Self::Output {
+        Point2::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Coord> Sub for Point2<T> {
+    type Output = Vec2<T>;
+
+    fn sub(self, rhs: Point2<T>) -> Self::Output {
+        Vec2::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+#[derive(Debug, Default, Ord, PartialOrd, Eq, PartialEq, Hash, Copy, Clone)]
+pub struct Vec2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Coord> Vec2<T> {
+    pub fn new(x: T, y: T) -> Self {
+        Vec2 { x, y }
+    }
+
+    pub fn manhattan_len(&self) -> T {
+        abs_diff(self.x, T::default()) + abs_diff(self.y, T::default())
+    }
+
+    pub fn dot(&self, other: &Vec2<T>) -> i128 {
+        self.x.widen() * other.x.widen() + self.y.widen() * other.y.widen()
+    }
+
+    pub fn cross(&self, other: &Vec2<T>) -> i128 {
+        self.x.widen() * other.y.widen() - self.y.widen() * other.x.widen()
+    }
+}
+
+impl<T: Coord> Add for Vec2<T> {
+    type Output = Vec2<T>;
+
+    fn add(self, rhs: Vec2<T>) -> Self::Output {
+        Vec2::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Coord> Sub for Vec2<T> {
+    type Output = Vec2<T>;
+
+    fn sub(self, rhs: Vec2<T>) -> Self::Output {
+        Vec2::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Coord> Mul<T> for Vec2<T> {
+    type Output = Vec2<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Vec2::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T: Coord + Neg<Output = T>> Neg for Vec2<T> {
+    type Output = Vec2<T>;
+
+    fn neg(self) -> Self::Output {
+        Vec2::new(-self.x, -self.y)
+    }
+}
+
+// Sign of the turn a -> b -> c: positive for counter-clockwise, negative for clockwise
+// and zero when the three points are collinear
+fn orientation<T: Coord>(a: Point2<T>, b: Point2<T>, c: Point2<T>) -> i128 {
+    let ab = (b.x.widen() - a.x.widen(), b.y.widen() - a.y.widen());
+    let ac = (c.x.widen() - a.x.widen(), c.y.widen() - a.y.widen());
+
+    (ab.0 * ac.1 - ab.1 * ac.0).signum()
+}
+
+// Whether `p`, already known to be collinear with the segment, lies within its bounds
+fn within_bounds<T: Coord>(segment: (Point2<T>, Point2<T>), p: Point2<T>) -> bool {
+    let (a, b) = segment;
+
+    a.x.min(b.x) <= p.x && p.x <= a.x.max(b.x) && a.y.min(b.y) <= p.y && p.y <= a.y.max(b.y)
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum SegmentIntersection<T> {
+    None,
+    // The segments cross or touch at exactly this point
+    Point(Point2<T>),
+    // The segments cross at a single point that isn't on the integer lattice
+    Crossing,
+    // The segments are collinear and share the stretch between these two points
+    Overlap(Point2<T>, Point2<T>),
+}
+
+pub fn segment_intersection<T: Coord>(
+    a: (Point2<T>, Point2<T>),
+    b: (Point2<T>, Point2<T>),
+) -> SegmentIntersection<T> {
+    let d1 = orientation(b.0, b.1, a.0);
+    let d2 = orientation(b.0, b.1, a.1);
+    let d3 = orientation(a.0, a.1, b.0);
+    let d4 = orientation(a.0, a.1, b.1);
+
+    if d1 == 0 && d2 == 0 && d3 == 0 && d4 == 0 {
+        // Collinear points are ordered along the line by their (x, y) ordering, so the
+        // shared stretch is just the intersection of the two sorted endpoint ranges
+        let lo = a.0.min(a.1).max(b.0.min(b.1));
+        let hi = a.0.max(a.1).min(b.0.max(b.1));
+
+        return match lo.cmp(&hi) {
+            std::cmp::Ordering::Less => SegmentIntersection::Overlap(lo, hi),
+            std::cmp::Ordering::Equal => SegmentIntersection::Point(lo),
+            std::cmp::Ordering::Greater => SegmentIntersection::None,
+        };
+    }
+
+    if d1 * d2 < 0 && d3 * d4 < 0 {
+        return match line_crossing(a, b) {
+            Some(point) => SegmentIntersection::Point(point),
+            None => SegmentIntersection::Crossing,
+        };
+    }
+
+    for (segment, point, side) in [(b, a.0, d1), (b, a.1, d2), (a, b.0, d3), (a, b.1, d4)] {
+        if side == 0 && within_bounds(segment, point) {
+            return SegmentIntersection::Point(point);
+        }
+    }
+
+    SegmentIntersection::None
+}
+
+// Exact crossing point of two properly crossing segments, when it lands on the lattice
+fn line_crossing<T: Coord>(
+    a: (Point2<T>, Point2<T>),
+    b: (Point2<T>, Point2<T>),
+) -> Option<Point2<T>> {
+    let (ax, ay) = (a.0.x.widen(), a.0.y.widen());
+    let (rx, ry) = (a.1.x.widen() - ax, a.1.y.widen() - ay);
+    let (sx, sy) = (b.1.x.widen() - b.0.x.widen(), b.1.y.widen() - b.0.y.widen());
+    let (qx, qy) = (b.0.x.widen() - ax, b.0.y.widen() - ay);
+
+    let denom = rx * sy - ry * sx;
+    let numer = qx * sy - qy * sx;
+
+    if (numer * rx) % denom != 0 || (numer * ry) % denom != 0 {
+        return None;
+    }
+
+    // The crossing lies inside both segments' bounding boxes, so it always fits in T
+    // unless the endpoints themselves didn't widen faithfully
+    let narrow = |value: i128| match T::narrow(value) {
+        Some(coord) => coord,
+        None => panic!("Crossing at {} doesn't fit the coordinate type", value),
+    };
+
+    Some(Point2::new(
+        narrow(ax + numer * rx / denom),
+        narrow(ay + numer * ry / denom),
+    ))
+}
+
+// Twice the polygon's area via the shoelace formula. Doubling keeps the result exact
+// for lattice polygons, whose area can be a half-integer.
+pub fn double_area<T: Coord>(polygon: &[Point2<T>]) -> u128 {
+    let mut sum = 0_i128;
+
+    for i in 0..polygon.len() {
+        let curr = polygon[i];
+        let next = polygon[(i + 1) % polygon.len()];
+        sum += curr.x.widen() * next.y.widen() - next.x.widen() * curr.y.widen();
+    }
+
+    sum.unsigned_abs()
+}
+
+// Number of lattice points lying on the polygon's edges
+pub fn boundary_points<T: Coord>(polygon: &[Point2<T>]) -> u128 {
+    (0..polygon.len())
+        .map(|i| {
+            let curr = polygon[i];
+            let next = polygon[(i + 1) % polygon.len()];
+            let dx = (next.x.widen() - curr.x.widen()).unsigned_abs();
+            let dy = (next.y.widen() - curr.y.widen()).unsigned_abs();
+            gcd(dx, dy)
+        })
+        .sum()
+}
+
+// Pick's theorem, A = i + b/2 - 1, solved for the number of interior lattice points.
+// `None` when the counts can't come from a simple lattice polygon, i.e. when there are
+// more boundary points than twice the area plus two.
+pub fn pick_interior_points(double_area: u128, boundary_points: u128) -> Option<u128> {
+    double_area
+        .checked_add(2)?
+        .checked_sub(boundary_points)
+        .map(|twice_interior| twice_interior / 2)
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Containment {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+// Even-odd ray cast towards +x, done entirely in integer arithmetic
+pub fn point_in_polygon<T: Coord>(point: Point2<T>, polygon: &[Point2<T>]) -> Containment {
+    let (px, py) = (point.x.widen(), point.y.widen());
+    let mut inside = false;
+
+    for i in 0..polygon.len() {
+        let a = polygon[i];
+        let b = polygon[(i + 1) % polygon.len()];
+
+        if orientation(a, b, point) == 0 && within_bounds((a, b), point) {
+            return Containment::Boundary;
+        }
+
+        let (ax, ay) = (a.x.widen(), a.y.widen());
+        let (bx, by) = (b.x.widen(), b.y.widen());
+
+        if (ay > py) != (by > py) {
+            // The edge crosses the ray's row; check the crossing lies to the right of
+            // `point` without dividing: px < ax + (py - ay) * (bx - ax) / (by - ay)
+            let lhs = (px - ax) * (by - ay);
+            let rhs = (py - ay) * (bx - ax);
+
+            if (by > ay && lhs < rhs) || (by < ay && lhs > rhs) {
+                inside = !inside;
+            }
+        }
+    }
+
+    if inside {
+        Containment::Inside
+    } else {
+        Containment::Outside
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::{
+        boundary_points, double_area, pick_interior_points, point_in_polygon, segment_intersection,
+        Containment, Coord, Direction, Point2, SegmentIntersection, Vec2,
+    };
+
+    #[test]
+    fn direction_rotations() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_right().turn_left(), direction);
+            assert_eq!(direction.opposite().opposite(), direction);
+            assert_eq!(direction.turn_right().turn_right(), direction.opposite());
+            assert_ne!(direction.turn_right(), direction);
+            assert_eq!(
+                direction.unit_vec::<i32>() + direction.opposite().unit_vec(),
+                Vec2::new(0, 0)
+            );
+        }
+
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.turn_right(), Direction::Up);
+        assert_eq!(Direction::Down.opposite(), Direction::Up);
+        assert_eq!(Direction::Up.unit_vec::<i64>(), Vec2::new(-1, 0));
+        assert_eq!(Direction::Left.unit_vec::<i8>(), Vec2::new(0, -1));
+    }
+
+    #[test]
+    fn steps_stay_inside_the_coordinate_type() {
+        let origin = Point2::new(0_usize, 0);
+
+        assert_eq!(origin.step(Direction::Down, 3), Some(Point2::new(3, 0)));
+        assert_eq!(origin.step(Direction::Right, 2), Some(Point2::new(0, 2)));
+        assert_eq!(origin.step(Direction::Up, 1), None);
+        assert_eq!(origin.step(Direction::Left, 1), None);
+        assert_eq!(Point2::new(250_u8, 0).step(Direction::Down, 10), None);
+        assert_eq!(
+            Point2::new(-5_i64, 5).step(Direction::Left, 7),
+            Some(Point2::new(-5, -2))
+        );
+
+        let p = Point2::new(4_i32, -1);
+        for direction in Direction::ALL {
+            assert_eq!(p.step(direction, 5), Some(p + direction.unit_vec() * 5));
+        }
+    }
+
+    #[test]
+    fn neighbours_and_grid_neighbours() {
+        let p = |x: usize, y: usize| Point2::new(x, y);
+
+        assert_eq!(
+            p(1, 1).neighbours().collect::<Vec<_>>(),
+            vec![
+                (Direction::Up, p(0, 1)),
+                (Direction::Right, p(1, 2)),
+                (Direction::Down, p(2, 1)),
+                (Direction::Left, p(1, 0)),
+            ]
+        );
+        assert_eq!(
+            p(0, 0).neighbours().collect::<Vec<_>>(),
+            vec![(Direction::Right, p(0, 1)), (Direction::Down, p(1, 0))]
+        );
+        assert_eq!(
+            p(2, 3).grid_neighbours(3, 4).collect::<Vec<_>>(),
+            vec![(Direction::Up, p(1, 3)), (Direction::Left, p(2, 2))]
+        );
+        assert_eq!(p(0, 0).grid_neighbours(1, 1).count(), 0);
+        assert_eq!(
+            Point2::new(-1_i32, 0)
+                .grid_neighbours(2, 2)
+                .collect::<Vec<_>>(),
+            vec![(Direction::Down, Point2::new(0, 0))]
+        );
+    }
+
+    #[test]
+    fn point_vector_arithmetic() {
+        let p = Point2::new(3_i64, -2);
+        let v = Vec2::new(1_i64, 4);
+
+        assert_eq!(p + v, Point2::new(4, 2));
+        assert_eq!(p - v, Point2::new(2, -6));
+        assert_eq!(Point2::new(4, 2) - p, v);
+        assert_eq!(v * 3, Vec2::new(3, 12));
+        assert_eq!(-v, Vec2::new(-1, -4));
+        assert_eq!(v.dot(&Vec2::new(2, 1)), 6);
+        assert_eq!(v.cross(&Vec2::new(2, 1)), -7);
+        assert_eq!(Vec2::new(-3_i8, 4).manhattan_len(), 7);
+    }
+
+    #[test]
+    fn distances() {
+        let a = Point2::new(1_usize, 7);
+        let b = Point2::new(4_usize, 2);
+
+        assert_eq!(a.manhattan(&b), 8);
+        assert_eq!(b.manhattan(&a), 8);
+        assert_eq!(a.chebyshev(&b), 5);
+        assert_eq!(Point2::new(-3_i32, 0).manhattan(&Point2::new(3, -1)), 7);
+    }
+
+    #[test]
+    fn area_and_pick() {
+        let square = [
+            Point2::new(0_u8, 0),
+            Point2::new(0, 4),
+            Point2::new(4, 4),
+            Point2::new(4, 0),
+        ];
+
+        assert_eq!(double_area(&square), 32);
+        assert_eq!(boundary_points(&square), 16);
+        assert_eq!(pick_interior_points(32, 16), Some(9));
+
+        let triangle = [Point2::new(0_i64, 0), Point2::new(3, 0), Point2::new(0, 3)];
+
+        assert_eq!(double_area(&triangle), 9);
+        assert_eq!(boundary_points(&triangle), 9);
+        assert_eq!(pick_interior_points(9, 9), Some(1));
+
+        // A polygon folded back on itself has no area but plenty of boundary
+        let folded = [Point2::new(0_i64, 0), Point2::new(0, 5)];
+
+        assert_eq!(double_area(&folded), 0);
+        assert_eq!(boundary_points(&folded), 10);
+        assert_eq!(pick_interior_points(0, 10), None);
+        assert_eq!(pick_interior_points(u128::MAX, 0), None);
+    }
+
+    #[test]
+    fn narrowing_checks_the_range() {
+        assert_eq!(u8::narrow(255), Some(255));
+        assert_eq!(u8::narrow(256), None);
+        assert_eq!(u8::narrow(-1), None);
+        assert_eq!(i64::narrow(i64::MIN as i128), Some(i64::MIN));
+        assert_eq!(i64::narrow(i64::MAX as i128 + 1), None);
+    }
+
+    #[test]
+    fn segment_intersections() {
+        let p = |x: i32, y: i32| Point2::new(x, y);
+
+        assert_eq!(
+            segment_intersection((p(0, 0), p(4, 4)), (p(0, 4), p(4, 0))),
+            SegmentIntersection::Point(p(2, 2))
+        );
+        assert_eq!(
+            segment_intersection((p(0, 0), p(4, 0)), (p(2, 0), p(2, 5))),
+            SegmentIntersection::Point(p(2, 0))
+        );
+        assert_eq!(
+            segment_intersection((p(0, 0), p(4, 0)), (p(4, 0), p(9, 0))),
+            SegmentIntersection::Point(p(4, 0))
+        );
+        assert_eq!(
+            segment_intersection((p(6, 0), p(0, 0)), (p(4, 0), p(9, 0))),
+            SegmentIntersection::Overlap(p(4, 0), p(6, 0))
+        );
+        assert_eq!(
+            segment_intersection((p(0, 0), p(4, 0)), (p(5, 0), p(9, 0))),
+            SegmentIntersection::None
+        );
+        assert_eq!(
+            segment_intersection((p(0, 0), p(3, 3)), (p(0, 1), p(1, 0))),
+            SegmentIntersection::Crossing
+        );
+        assert_eq!(
+            segment_intersection((p(0, 0), p(4, 4)), (p(0, 1), p(3, 4))),
+            SegmentIntersection::None
+        );
+    }
+
+    #[test]
+    fn containment() {
+        let polygon = [
+            Point2::new(0_i64, 0),
+            Point2::new(0, 6),
+            Point2::new(3, 6),
+            Point2::new(3, 3),
+            Point2::new(6, 3),
+            Point2::new(6, 0),
+        ];
+
+        assert_eq!(
+            point_in_polygon(Point2::new(1, 1), &polygon),
+            Containment::Inside
+        );
+        assert_eq!(
+            point_in_polygon(Point2::new(5, 2), &polygon),
+            Containment::Inside
+        );
+        assert_eq!(
+            point_in_polygon(Point2::new(4, 4), &polygon),
+            Containment::Outside
+        );
+        assert_eq!(
+            point_in_polygon(Point2::new(3, 5), &polygon),
+            Containment::Boundary
+        );
+        assert_eq!(
+            point_in_polygon(Point2::new(0, 0), &polygon),
+            Containment::Boundary
+        );
+        assert_eq!(
+            point_in_polygon(Point2::new(-1, 3), &polygon),
+            Containment::Outside
+        );
+    }
+}
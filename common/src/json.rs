@@ -0,0 +1,32 @@
+// Quotes and escapes a string for hand-written JSON output
+pub fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json::json_string;
+
+    #[test]
+    fn escapes_strings() {
+        assert_eq!(json_string("rn=1"), "\"rn=1\"");
+        assert_eq!(json_string(""), "\"\"");
+        assert_eq!(json_string("a\"b\\c"), "\"a\\\"b\\\\c\"");
+        assert_eq!(json_string("x\ny\tz"), "\"x\\ny\\u0009z\"");
+        assert_eq!(json_string("fünf"), "\"fünf\"");
+    }
+}
@@ -1,3 +1,6 @@
 pub mod filereader;
 pub mod filewriter;
 pub mod formatting;
+pub mod geometry;
+pub mod json;
+pub mod unordered_pair;
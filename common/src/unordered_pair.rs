@@ -0,0 +1,292 @@
+use std::collections::hash_map;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// The elements are always stored smallest-first, so the derived Eq/Hash/Ord impls are
+// symmetric without any extra work: `(a, b)` and `(b, a)` are the same pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct UnorderedPair<T>(T, T);
+
+impl<T: Ord> UnorderedPair<T> {
+    pub fn new(a: T, b: T) -> Self {
+        if a <= b {
+            UnorderedPair(a, b)
+        } else {
+            UnorderedPair(b, a)
+        }
+    }
+
+    pub fn first(&self) -> &T {
+        &self.0
+    }
+
+    pub fn second(&self) -> &T {
+        &self.1
+    }
+
+    pub fn is_loop(&self) -> bool {
+        self.0 == self.1
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        &self.0 == value || &self.1 == value
+    }
+
+    pub fn other(&self, value: &T) -> Option<&T> {
+        if &self.0 == value {
+            Some(&self.1)
+        } else if &self.1 == value {
+            Some(&self.0)
+        } else {
+            None
+        }
+    }
+
+    pub fn into_inner(self) -> (T, T) {
+        (self.0, self.1)
+    }
+}
+
+impl<T: Ord> From<(T, T)> for UnorderedPair<T> {
+    fn from((a, b): (T, T)) -> Self {
+        UnorderedPair::new(a, b)
+    }
+}
+
+pub struct Pairs<'a, T> {
+    items: &'a [T],
+    i: usize,
+    j: usize,
+}
+
+impl<'a, T: Ord> Iterator for Pairs<'a, T> {
+    type Item = UnorderedPair<&'a T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.j >= self.items.len() {
+            self.i += 1;
+            self.j = self.i + 1;
+        }
+
+        if self.j >= self.items.len() {
+            return None;
+        }
+
+        let pair = UnorderedPair::new(&self.items[self.i], &self.items[self.j]);
+        self.j += 1;
+
+        Some(pair)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.items.len();
+
+        // Pairs still to come in row `i`, plus every pair in the rows after it
+        let remaining = if self.i >= n {
+            0
+        } else {
+            let rest_of_row = n.saturating_sub(self.j);
+            let later = n - self.i - 1;
+            rest_of_row + later * later.saturating_sub(1) / 2
+        };
+
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T: Ord> ExactSizeIterator for Pairs<'a, T> {}
+
+// Every pair of distinct positions in `items`, each yielded once
+pub fn pairs<T: Ord>(items: &[T]) -> Pairs<'_, T> {
+    Pairs { items, i: 0, j: 1 }
+}
+
+#[derive(Debug, Clone)]
+pub struct UnorderedPairMap<K, V> {
+    entries: HashMap<UnorderedPair<K>, V>,
+}
+
+impl<K: Ord + Hash + Clone, V> UnorderedPairMap<K, V> {
+    pub fn new() -> Self {
+        UnorderedPairMap {
+            entries: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, a: K, b: K, value: V) -> Option<V> {
+        self.entries.insert(UnorderedPair::new(a, b), value)
+    }
+
+    pub fn get(&self, a: &K, b: &K) -> Option<&V> {
+        self.entries.get(&UnorderedPair::new(a.clone(), b.clone()))
+    }
+
+    pub fn get_mut(&mut self, a: &K, b: &K) -> Option<&mut V> {
+        self.entries
+            .get_mut(&UnorderedPair::new(a.clone(), b.clone()))
+    }
+
+    pub fn contains(&self, a: &K, b: &K) -> bool {
+        self.get(a, b).is_some()
+    }
+
+    pub fn remove(&mut self, a: &K, b: &K) -> Option<V> {
+        self.entries
+            .remove(&UnorderedPair::new(a.clone(), b.clone()))
+    }
+
+    pub fn entry(&mut self, a: K, b: K) -> hash_map::Entry<'_, UnorderedPair<K>, V> {
+        self.entries.entry(UnorderedPair::new(a, b))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> hash_map::Iter<'_, UnorderedPair<K>, V> {
+        self.entries.iter()
+    }
+
+    pub fn values(&self) -> hash_map::Values<'_, UnorderedPair<K>, V> {
+        self.entries.values()
+    }
+}
+
+impl<K: Ord + Hash + Clone, V> Default for UnorderedPairMap<K, V> {
+    fn default() -> Self {
+        UnorderedPairMap::new()
+    }
+}
+
+impl<K: Ord + Hash + Clone, V> FromIterator<((K, K), V)> for UnorderedPairMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = ((K, K), V)>>(iter: I) -> Self {
+        let mut map = UnorderedPairMap::new();
+
+        for ((a, b), value) in iter {
+            map.insert(a, b, value);
+        }
+
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::unordered_pair::{pairs, UnorderedPair, UnorderedPairMap};
+    use std::collections::HashSet;
+
+    #[test]
+    fn pair_is_normalised() {
+        let pair = UnorderedPair::new(5, 2);
+
+        assert_eq!(pair, UnorderedPair::new(2, 5));
+        assert_eq!(pair.into_inner(), (2, 5));
+        assert_eq!(pair.first(), &2);
+        assert_eq!(pair.second(), &5);
+        assert_eq!(UnorderedPair::from((5, 2)), pair);
+    }
+
+    #[test]
+    fn pair_hash_is_symmetric() {
+        let set: HashSet<UnorderedPair<(usize, usize)>> = [
+            UnorderedPair::new((0, 1), (3, 4)),
+            UnorderedPair::new((3, 4), (0, 1)),
+            UnorderedPair::new((3, 4), (3, 4)),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn pair_ordering() {
+        let mut sorted = vec![
+            UnorderedPair::new(3, 1),
+            UnorderedPair::new(2, 2),
+            UnorderedPair::new(1, 2),
+        ];
+        sorted.sort();
+
+        assert_eq!(
+            sorted,
+            vec![
+                UnorderedPair::new(1, 2),
+                UnorderedPair::new(1, 3),
+                UnorderedPair::new(2, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn pair_membership() {
+        let pair = UnorderedPair::new('b', 'a');
+
+        assert!(pair.contains(&'a'));
+        assert!(!pair.contains(&'c'));
+        assert_eq!(pair.other(&'a'), Some(&'b'));
+        assert_eq!(pair.other(&'c'), None);
+        assert!(!pair.is_loop());
+        assert!(UnorderedPair::new('a', 'a').is_loop());
+    }
+
+    #[test]
+    fn pairs_of_slice() {
+        let items = [4, 1, 3, 2];
+        let all: Vec<(i32, i32)> = pairs(&items)
+            .map(|pair| (**pair.first(), **pair.second()))
+            .collect();
+
+        assert_eq!(all, vec![(1, 4), (3, 4), (2, 4), (1, 3), (1, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn pairs_size_hint() {
+        for n in 0..8 {
+            let items: Vec<usize> = (0..n).collect();
+            let mut iter = pairs(&items);
+            let mut expected = n * n.saturating_sub(1) / 2;
+
+            assert_eq!(iter.len(), expected);
+
+            while iter.next().is_some() {
+                expected -= 1;
+                assert_eq!(iter.len(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn pair_map_is_symmetric() {
+        let mut map = UnorderedPairMap::new();
+
+        assert!(map.is_empty());
+        assert_eq!(map.insert("a", "b", 3), None);
+        assert_eq!(map.insert("b", "a", 4), Some(3));
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.get(&"a", &"b"), Some(&4));
+        assert_eq!(map.get(&"b", &"a"), Some(&4));
+        assert!(!map.contains(&"a", &"c"));
+
+        *map.get_mut(&"b", &"a").unwrap() += 1;
+        *map.entry("c", "a").or_insert(0) += 7;
+
+        assert_eq!(map.values().sum::<i32>(), 12);
+        assert_eq!(map.remove(&"a", &"b"), Some(5));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn pair_map_from_iter() {
+        let map: UnorderedPairMap<u8, u32> = [((1, 2), 10), ((2, 1), 20), ((2, 3), 5)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&1, &2), Some(&20));
+    }
+}
@@ -1,4 +1,5 @@
 use common::filewriter;
+use common::geometry::Point2;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt;
 
@@ -940,51 +941,10 @@ impl PipeGraph {
 
     // Get allowable neighbors for the given coordinates
     fn get_neighbors(&self, coords: (usize, usize)) -> Vec<(usize, usize)> {
-        let (r, c) = coords;
-        // let cell = self.get_cell(coords.0, coords.1);
-        let mut neighbors = Vec::with_capacity(8);
-
-        if r > 0 {
-            // Top
-            neighbors.push((r - 1, c));
-
-            // if c > 0 {
-            //     // Top left
-            //     neighbors.push((r - 1, c - 1));
-            // }
-
-            // if c < self.cells[0].len() - 1 {
-            //     // Top right
-            //     neighbors.push((r - 1, c + 1));
-            // }
-        }
-
-        if c > 0 {
-            // Left
-            neighbors.push((r, c - 1));
-        }
-
-        if r < self.cells.len() - 1 {
-            // Bottom
-            neighbors.push((r + 1, c));
-
-            // if c < self.cells[0].len() - 1 {
-            //     // Bottom right
-            //     neighbors.push((r + 1, c + 1));
-            // }
-
-            // if c > 0 {
-            //     // Bottom left
-            //     neighbors.push((r + 1, c - 1));
-            // }
-        }
-
-        if c < self.cells[0].len() - 1 {
-            // Right
-            neighbors.push((r, c + 1));
-        }
-
-        neighbors
+        Point2::new(coords.0, coords.1)
+            .grid_neighbours(self.cells.len(), self.cells[0].len())
+            .map(|(_, p)| (p.x, p.y))
+            .collect()
     }
 }
 
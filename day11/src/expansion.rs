@@ -0,0 +1,135 @@
+use common::geometry::Point2;
+
+pub struct ExpandedUniverse {
+    galaxies: Vec<Point2<u128>>,
+}
+
+impl ExpandedUniverse {
+    pub fn new(grid: &[Vec<char>], expansion_factor: u64) -> Self {
+        let height = grid.len();
+        let width = grid.first().map_or(0, |row| row.len());
+
+        let empty_rows: Vec<bool> = grid
+            .iter()
+            .map(|row| row.iter().all(|&c| c != '#'))
+            .collect();
+        let empty_cols: Vec<bool> = (0..width)
+            .map(|col| (0..height).all(|row| grid[row][col] != '#'))
+            .collect();
+
+        let row_offsets = Self::expanded_offsets(&empty_rows, expansion_factor);
+        let col_offsets = Self::expanded_offsets(&empty_cols, expansion_factor);
+
+        let galaxies = grid
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, &c)| c == '#')
+                    .map(|(j, _)| (i, j))
+                    .collect::<Vec<_>>()
+            })
+            .map(|(i, j)| Point2::new(row_offsets[i], col_offsets[j]))
+            .collect();
+
+        ExpandedUniverse { galaxies }
+    }
+
+    // Maps each original index to its position once every empty line before it has been
+    // widened to `expansion_factor` lines (a running prefix sum over the empty flags)
+    fn expanded_offsets(empty: &[bool], expansion_factor: u64) -> Vec<u128> {
+        let mut offsets = Vec::with_capacity(empty.len());
+        let mut position = 0_u128;
+
+        for &is_empty in empty {
+            offsets.push(position);
+            position += if is_empty {
+                expansion_factor as u128
+            } else {
+                1
+            };
+        }
+
+        offsets
+    }
+
+    pub fn get_distance_sum(&self) -> u128 {
+        let rows = self.galaxies.iter().map(|galaxy| galaxy.x).collect();
+        let cols = self.galaxies.iter().map(|galaxy| galaxy.y).collect();
+
+        Self::axis_distance_sum(rows) + Self::axis_distance_sum(cols)
+    }
+
+    // Manhattan distance splits per axis, and once an axis is sorted each coordinate
+    // contributes `coord * idx - (sum of all coords before it)` to the pairwise total
+    fn axis_distance_sum(mut coords: Vec<u128>) -> u128 {
+        coords.sort_unstable();
+
+        let mut total = 0_u128;
+        let mut prefix = 0_u128;
+
+        for (idx, &coord) in coords.iter().enumerate() {
+            total += coord * idx as u128 - prefix;
+            prefix += coord;
+        }
+
+        total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::expansion::ExpandedUniverse;
+    use common::geometry::Point2;
+    use common::unordered_pair::pairs;
+
+    fn example_grid() -> Vec<Vec<char>> {
+        [
+            "...#......",
+            ".......#..",
+            "#.........",
+            "..........",
+            "......#...",
+            ".#........",
+            ".........#",
+            "..........",
+            ".......#..",
+            "#...#.....",
+        ]
+        .iter()
+        .map(|line| line.chars().collect())
+        .collect()
+    }
+
+    #[test]
+    fn distance_sum_example() {
+        let grid = example_grid();
+
+        assert_eq!(ExpandedUniverse::new(&grid, 2).get_distance_sum(), 374);
+        assert_eq!(ExpandedUniverse::new(&grid, 10).get_distance_sum(), 1030);
+        assert_eq!(ExpandedUniverse::new(&grid, 100).get_distance_sum(), 8410);
+    }
+
+    #[test]
+    fn no_expansion_matches_raw_grid() {
+        let grid = example_grid();
+        let universe = ExpandedUniverse::new(&grid, 1);
+
+        assert_eq!(universe.galaxies.len(), 9);
+        assert_eq!(universe.galaxies[0], Point2::new(0, 3));
+        assert_eq!(universe.galaxies[8], Point2::new(9, 4));
+    }
+
+    #[test]
+    fn axis_sums_match_pairwise_manhattan() {
+        for factor in [1, 2, 7, 1_000_000] {
+            let universe = ExpandedUniverse::new(&example_grid(), factor);
+            let pairwise: u128 = pairs(&universe.galaxies)
+                .map(|pair| pair.first().manhattan(pair.second()))
+                .sum();
+
+            assert_eq!(universe.get_distance_sum(), pairwise, "factor {}", factor);
+        }
+    }
+}
@@ -1,15 +1,12 @@
-use crate::Direction::North;
+mod platform;
+mod program;
+
+use crate::platform::{Direction, Platform};
+use crate::program::{parse_direction, TiltProgram};
 use common::filereader;
-use common::formatting::format_grid;
-use std::collections::HashSet;
-
-#[derive(Debug)]
-enum Direction {
-    North,
-    West,
-    South,
-    East,
-}
+use std::env;
+
+const PUZZLE_CYCLES: u64 = 1_000_000_000;
 
 const CYCLE_DIRECTIONS: [Direction; 4] = [
     Direction::North,
@@ -18,165 +15,234 @@ const CYCLE_DIRECTIONS: [Direction; 4] = [
     Direction::East,
 ];
 
-fn get_new_rock_coords(
-    lines: &Vec<Vec<char>>,
-    direction: &Direction,
-    row: usize,
-    col: usize,
-) -> (usize, usize) {
-    let mut new_row = row;
-    let mut new_col = col;
-
-    match direction {
-        Direction::North => {
-            for i in (0..row).rev() {
-                match lines[i][col] {
-                    '.' => {
-                        new_row = i;
-                    }
-                    _ => break,
-                }
-            }
-        }
-        Direction::West => {
-            for j in (0..col).rev() {
-                match lines[row][j] {
-                    '.' => {
-                        new_col = j;
-                    }
-                    _ => break,
-                }
-            }
-        }
-        Direction::South => {
-            for i in (row + 1)..lines.len() {
-                match lines[i][col] {
-                    '.' => {
-                        new_row = i;
-                    }
-                    _ => break,
-                }
-            }
-        }
-        Direction::East => {
-            for j in (col + 1)..lines[0].len() {
-                match lines[row][j] {
-                    '.' => {
-                        new_col = j;
-                    }
-                    _ => break,
-                }
-            }
-        }
+fn parse_platform(lines: &[String]) -> Platform {
+    match Platform::parse(lines) {
+        Ok(platform) => platform,
+        Err(e) => panic!("Bad platform: {:?}", e),
     }
-
-    (new_row, new_col)
 }
 
-fn roll(mut lines: Vec<Vec<char>>, direction: &Direction) -> Vec<Vec<char>> {
-    match direction {
-        North => {
-            for i in 0..lines.len() {
-                for j in 0..lines[0].len() {
-                    if lines[i][j] == 'O' {
-                        let (new_i, new_j) = get_new_rock_coords(&lines, direction, i, j);
-                        lines[i][j] = '.';
-                        lines[new_i][new_j] = 'O';
-                    }
-                }
-            }
-
-            lines
-        }
-        Direction::West => {
-            for j in 0..lines[0].len() {
-                for i in 0..lines.len() {
-                    if lines[i][j] == 'O' {
-                        let (new_i, new_j) = get_new_rock_coords(&lines, direction, i, j);
-                        lines[i][j] = '.';
-                        lines[new_i][new_j] = 'O';
-                    }
-                }
-            }
-
-            lines
-        }
-        Direction::South => {
-            for i in (0..lines.len()).rev() {
-                for j in 0..lines[0].len() {
-                    if lines[i][j] == 'O' {
-                        let (new_i, new_j) = get_new_rock_coords(&lines, direction, i, j);
-                        lines[i][j] = '.';
-                        lines[new_i][new_j] = 'O';
-                    }
-                }
-            }
-
-            lines
-        }
-        Direction::East => {
-            for j in (0..lines[0].len()).rev() {
-                for i in 0..lines.len() {
-                    if lines[i][j] == 'O' {
-                        let (new_i, new_j) = get_new_rock_coords(&lines, direction, i, j);
-                        lines[i][j] = '.';
-                        lines[new_i][new_j] = 'O';
-                    }
-                }
-            }
+fn spin_cycle() -> TiltProgram {
+    TiltProgram::new(CYCLE_DIRECTIONS.to_vec()).unwrap()
+}
 
-            lines
-        }
-    }
+fn run_cycles(platform: Platform, num_cycles: u64) -> Platform {
+    spin_cycle().run(platform, num_cycles)
 }
 
-fn run_cycles(mut lines: Vec<Vec<char>>, num_cycles: u32) -> Vec<Vec<char>> {
-    for cycle in 0..num_cycles {
-        println!("Running cycle {}", cycle);
-        for direction in CYCLE_DIRECTIONS {
-            lines = roll(lines, &direction);
-        }
-    }
+fn solve(lines: Vec<String>) -> u64 {
+    let mut platform = parse_platform(&lines);
+    platform.tilt(Direction::North);
 
-    println!("{}", format_grid(&lines));
+    platform.north_load()
+}
 
-    lines
+fn solve2(lines: Vec<String>) -> u64 {
+    run_cycles(parse_platform(&lines), PUZZLE_CYCLES).north_load()
 }
 
-fn solve(lines: Vec<String>) -> u32 {
-    let char_vec: Vec<Vec<char>> = lines.iter().map(|line| line.chars().collect()).collect();
-    let rolled_forward = roll(char_vec, &North);
+// Load against `wall` after running `program` over the platform `passes` times
+fn solve_program(lines: Vec<String>, program: &TiltProgram, passes: u64, wall: Direction) -> u64 {
+    program.run(parse_platform(&lines), passes).load(wall)
+}
 
-    rolled_forward
-        .iter()
-        .enumerate()
-        .map(|(i, row)| {
-            let num_rollable_rocks = row.iter().filter(|&&c| c == 'O').count() as u32;
-            (rolled_forward.len() - i) as u32 * num_rollable_rocks
-        })
-        .sum()
+// Optional arguments: a tilt program such as `N,E,E,S`, how many passes of it to run and
+// which wall to measure against; the last two default to the puzzle's billion passes and
+// north. With no arguments at all the puzzle's spin cycle is run instead.
+fn read_program_args(args: &[String]) -> Option<(TiltProgram, u64, Direction)> {
+    let program = match args.first()?.parse() {
+        Ok(program) => program,
+        Err(e) => panic!("Bad tilt program {}: {:?}", args[0], e),
+    };
+
+    let passes = match args.get(1) {
+        Some(arg) => match arg.parse() {
+            Ok(passes) => passes,
+            Err(e) => panic!("Bad pass count {}: {:?}", arg, e),
+        },
+        None => PUZZLE_CYCLES,
+    };
+
+    let wall = match args.get(2) {
+        Some(arg) => match parse_direction(arg) {
+            Ok(wall) => wall,
+            Err(e) => panic!("Bad wall {}: {:?}", arg, e),
+        },
+        None => Direction::North,
+    };
+
+    Some((program, passes, wall))
 }
 
-fn solve2(lines: Vec<String>) -> u32 {
-    let char_vec: Vec<Vec<char>> = lines.iter().map(|line| line.chars().collect()).collect();
-    let cycled_out = run_cycles(char_vec, 10000);
+// `--steps N` anywhere in the arguments takes them out and asks for the first N tilts to
+// be printed before the result
+fn take_steps_flag(args: &mut Vec<String>) -> Option<usize> {
+    let flag = args.iter().position(|arg| arg == "--steps")?;
+    let count = match args.get(flag + 1).map(|arg| arg.parse::<usize>()) {
+        Some(Ok(count)) => count,
+        _ => panic!("--steps needs a number of tilts"),
+    };
+
+    args.drain(flag..flag + 2);
+    Some(count)
+}
 
-    cycled_out
-        .iter()
-        .enumerate()
-        .map(|(i, row)| {
-            let num_rollable_rocks = row.iter().filter(|&&c| c == 'O').count() as u32;
-            (cycled_out.len() - i) as u32 * num_rollable_rocks
+// The platform after each of the first `count` tilts, headed by the tilt and the load
+fn describe_steps(
+    lines: &[String],
+    program: &TiltProgram,
+    count: usize,
+    wall: Direction,
+) -> Vec<String> {
+    program
+        .steps(parse_platform(lines))
+        .take(count)
+        .flat_map(|step| {
+            let mut described = vec![format!(
+                "Step {} ({:?}): load {}",
+                step.step + 1,
+                step.direction,
+                step.platform.load(wall)
+            )];
+            described.extend(
+                step.platform
+                    .to_grid()
+                    .iter()
+                    .map(|row| row.iter().collect::<String>()),
+            );
+            described
         })
-        .sum()
+        .collect()
 }
 
 fn main() {
     match filereader::read_file("./day14/resources/input.txt") {
         Ok(lines) => {
-            let result = solve2(lines);
+            let mut args: Vec<String> = env::args().skip(1).collect();
+            let steps = take_steps_flag(&mut args);
+            let program_args = read_program_args(&args);
+
+            if let Some(count) = steps {
+                let (program, wall) = match &program_args {
+                    Some((program, _, wall)) => (program.clone(), *wall),
+                    None => (spin_cycle(), Direction::North),
+                };
+
+                for line in describe_steps(&lines, &program, count, wall) {
+                    println!("{}", line);
+                }
+            }
+
+            let result = match program_args {
+                Some((program, passes, wall)) => solve_program(lines, &program, passes, wall),
+                None => solve2(lines),
+            };
             println!("{:?}", result);
         }
         Err(e) => panic!("{}", e),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::platform::Direction;
+    use crate::{
+        describe_steps, parse_platform, run_cycles, solve, solve2, solve_program, spin_cycle,
+    };
+
+    pub(crate) fn example_lines() -> Vec<String> {
+        [
+            "O....#....",
+            "O.OO#....#",
+            ".....##...",
+            "OO.#O....O",
+            ".O.....O#.",
+            "O.#..O.#.#",
+            "..O..#O..O",
+            ".......O..",
+            "#....###..",
+            "#OO..#....",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect()
+    }
+
+    #[test]
+    fn example_spin_cycles() {
+        assert_eq!(solve(example_lines()), 136);
+
+        let after_three = [
+            ".....#....",
+            "....#...O#",
+            ".....##...",
+            "..O#......",
+            ".....OOO#.",
+            ".O#...O#.#",
+            "....O#...O",
+            ".......OOO",
+            "#...O###.O",
+            "#.OOO#...O",
+        ];
+        let cycled = run_cycles(parse_platform(&example_lines()), 3);
+
+        assert_eq!(
+            cycled.to_grid(),
+            after_three
+                .iter()
+                .map(|line| line.chars().collect::<Vec<char>>())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(cycled.north_load(), 69);
+    }
+
+    #[test]
+    fn example_billion_cycles() {
+        assert_eq!(solve2(example_lines()), 64);
+    }
+
+    #[test]
+    fn custom_programs() {
+        let lines = example_lines();
+
+        assert_eq!(
+            solve_program(lines.clone(), &"N".parse().unwrap(), 1, Direction::North),
+            136
+        );
+        assert_eq!(
+            solve_program(
+                lines.clone(),
+                &"N,W,S,E".parse().unwrap(),
+                3,
+                Direction::North
+            ),
+            69
+        );
+        // Tilting east twice in a row is the same as tilting once
+        assert_eq!(
+            solve_program(
+                lines,
+                &"N,E,E,S".parse().unwrap(),
+                1_000_000,
+                Direction::East
+            ),
+            solve_program(
+                example_lines(),
+                &"N,E,S".parse().unwrap(),
+                1_000_000,
+                Direction::East
+            )
+        );
+    }
+
+    #[test]
+    fn describes_each_tilt() {
+        let described = describe_steps(&example_lines(), &spin_cycle(), 2, Direction::North);
+
+        assert_eq!(described.len(), 2 * 11);
+        assert_eq!(described[0], "Step 1 (North): load 136");
+        assert_eq!(described[1], "OOOO.#.O..");
+        assert_eq!(described[11], "Step 2 (West): load 136");
+        assert_eq!(described[12], "OOOO.#O...");
+    }
+}
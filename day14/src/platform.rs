@@ -0,0 +1,303 @@
+// Each row and column is packed into a single u128, so platforms can be at most this wide
+const MAX_SIDE: usize = 128;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Direction {
+    North,
+    West,
+    South,
+    East,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PlatformError {
+    Empty,
+    TooLarge { rows: usize, cols: usize },
+    RaggedRow(usize),
+    InvalidTile { row: usize, col: usize, tile: char },
+}
+
+// A maximal run of open tiles between cube rocks (or the platform's edges) in one row or
+// column, as a bit mask over that line
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct Segment {
+    mask: u128,
+    low: u32,
+    high: u32,
+}
+
+impl Segment {
+    // Round rocks of `line` that sit in this segment, piled up against its low or high end
+    fn pile(&self, line: u128, towards_low: bool) -> u128 {
+        let count = (line & self.mask).count_ones();
+
+        if count == 0 {
+            0
+        } else if towards_low {
+            ones(count) << self.low
+        } else {
+            ones(count) << (self.high + 1 - count)
+        }
+    }
+}
+
+// Round rocks are kept twice, as row bitsets (bit j is column j, west first) and as column
+// bitsets (bit i is row i, north first); a tilt works on whichever view runs along it and
+// then rebuilds the other. Cube rocks never move, so their segments are worked out once.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Platform {
+    rows: usize,
+    cols: usize,
+    round_rows: Vec<u128>,
+    round_cols: Vec<u128>,
+    cube_rows: Vec<u128>,
+    row_segments: Vec<Vec<Segment>>,
+    col_segments: Vec<Vec<Segment>>,
+}
+
+impl Platform {
+    pub fn parse(lines: &[String]) -> Result<Self, PlatformError> {
+        let rows = lines.len();
+        let cols = lines.first().ok_or(PlatformError::Empty)?.chars().count();
+
+        if rows > MAX_SIDE || cols > MAX_SIDE {
+            return Err(PlatformError::TooLarge { rows, cols });
+        }
+
+        let mut round_rows = vec![0_u128; rows];
+        let mut cube_rows = vec![0_u128; rows];
+
+        for (row, line) in lines.iter().enumerate() {
+            if line.chars().count() != cols {
+                return Err(PlatformError::RaggedRow(row));
+            }
+
+            for (col, tile) in line.chars().enumerate() {
+                match tile {
+                    'O' => round_rows[row] |= 1 << col,
+                    '#' => cube_rows[row] |= 1 << col,
+                    '.' => {}
+                    _ => return Err(PlatformError::InvalidTile { row, col, tile }),
+                }
+            }
+        }
+
+        let cube_cols = transpose(&cube_rows, cols);
+
+        Ok(Platform {
+            rows,
+            cols,
+            round_cols: transpose(&round_rows, cols),
+            round_rows,
+            row_segments: cube_rows
+                .iter()
+                .map(|&cubes| find_segments(cubes, cols))
+                .collect(),
+            col_segments: cube_cols
+                .iter()
+                .map(|&cubes| find_segments(cubes, rows))
+                .collect(),
+            cube_rows,
+        })
+    }
+
+    pub fn is_round_rock(&self, row: usize, col: usize) -> bool {
+        self.round_rows[row] >> col & 1 == 1
+    }
+
+    pub fn is_cube_rock(&self, row: usize, col: usize) -> bool {
+        self.cube_rows[row] >> col & 1 == 1
+    }
+
+    pub fn tilt(&mut self, direction: Direction) {
+        match direction {
+            Direction::North | Direction::South => {
+                let towards_low = direction == Direction::North;
+
+                for (line, segments) in self.round_cols.iter_mut().zip(self.col_segments.iter()) {
+                    *line = segments
+                        .iter()
+                        .fold(0, |piled, s| piled | s.pile(*line, towards_low));
+                }
+
+                self.round_rows = transpose(&self.round_cols, self.rows);
+            }
+            Direction::West | Direction::East => {
+                let towards_low = direction == Direction::West;
+
+                for (line, segments) in self.round_rows.iter_mut().zip(self.row_segments.iter()) {
+                    *line = segments
+                        .iter()
+                        .fold(0, |piled, s| piled | s.pile(*line, towards_low));
+                }
+
+                self.round_cols = transpose(&self.round_rows, self.cols);
+            }
+        }
+    }
+
+    pub fn get_round_rows(&self) -> &[u128] {
+        &self.round_rows
+    }
+
+    // Each round rock weighs in with the number of rows (or columns) from it to the edge
+    // opposite `wall`, inclusive
+    pub fn load(&self, wall: Direction) -> u64 {
+        let (lines, len) = match wall {
+            Direction::North | Direction::South => (&self.round_rows, self.rows),
+            Direction::West | Direction::East => (&self.round_cols, self.cols),
+        };
+
+        lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                let distance = match wall {
+                    Direction::North | Direction::West => len - i,
+                    Direction::South | Direction::East => i + 1,
+                };
+                distance as u64 * line.count_ones() as u64
+            })
+            .sum()
+    }
+
+    pub fn north_load(&self) -> u64 {
+        self.load(Direction::North)
+    }
+
+    pub fn to_grid(&self) -> Vec<Vec<char>> {
+        (0..self.rows)
+            .map(|row| {
+                (0..self.cols)
+                    .map(|col| {
+                        if self.is_round_rock(row, col) {
+                            'O'
+                        } else if self.is_cube_rock(row, col) {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+fn ones(count: u32) -> u128 {
+    if count == 128 {
+        u128::MAX
+    } else {
+        (1 << count) - 1
+    }
+}
+
+// Turns `len` bitsets over `width` positions into `width` bitsets over `len` positions
+fn transpose(lines: &[u128], width: usize) -> Vec<u128> {
+    let mut transposed = vec![0_u128; width];
+
+    for (i, &line) in lines.iter().enumerate() {
+        let mut remaining = line;
+
+        while remaining != 0 {
+            let j = remaining.trailing_zeros() as usize;
+            transposed[j] |= 1 << i;
+            remaining &= remaining - 1;
+        }
+    }
+
+    transposed
+}
+
+fn find_segments(cubes: u128, len: usize) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+
+    for pos in 0..=len {
+        if pos == len || cubes >> pos & 1 == 1 {
+            if pos > start {
+                segments.push(Segment {
+                    mask: ones((pos - start) as u32) << start,
+                    low: start as u32,
+                    high: pos as u32 - 1,
+                });
+            }
+            start = pos + 1;
+        }
+    }
+
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::platform::{Direction, Platform, PlatformError};
+
+    fn platform(lines: &[&str]) -> Platform {
+        let lines: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+        Platform::parse(&lines).unwrap()
+    }
+
+    fn rendered(platform: &Platform) -> Vec<String> {
+        platform
+            .to_grid()
+            .iter()
+            .map(|row| row.iter().collect())
+            .collect()
+    }
+
+    #[test]
+    fn tilts_stop_at_cube_rocks() {
+        let mut tilted = platform(&["O.O#.O", ".O..#.", "O#O..O"]);
+
+        tilted.tilt(Direction::East);
+        assert_eq!(rendered(&tilted), [".OO#.O", "...O#.", "O#..OO"]);
+
+        tilted.tilt(Direction::South);
+        assert_eq!(rendered(&tilted), ["...#..", ".O..#O", "O#OOOO"]);
+    }
+
+    #[test]
+    fn north_load_counts_rows_from_the_south_edge() {
+        let mut tilted = platform(&["..#", "O.O", "OO."]);
+
+        assert_eq!(tilted.north_load(), 2 + 2 + 1 + 1);
+
+        tilted.tilt(Direction::North);
+        assert_eq!(rendered(&tilted), ["OO#", "O.O", "..."]);
+        assert_eq!(tilted.north_load(), 3 + 3 + 2 + 2);
+    }
+
+    #[test]
+    fn load_against_every_wall() {
+        let tilted = platform(&["O..#", "..O.", ".O.O"]);
+
+        assert_eq!(tilted.load(Direction::North), 3 + 2 + 1 + 1);
+        assert_eq!(tilted.load(Direction::South), 1 + 2 + 3 + 3);
+        assert_eq!(tilted.load(Direction::West), 4 + 3 + 2 + 1);
+        assert_eq!(tilted.load(Direction::East), 1 + 2 + 3 + 4);
+    }
+
+    #[test]
+    fn rejects_bad_platforms() {
+        let parse = |lines: &[&str]| {
+            let lines: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+            Platform::parse(&lines)
+        };
+
+        assert_eq!(parse(&[]), Err(PlatformError::Empty));
+        assert_eq!(parse(&["..", "."]), Err(PlatformError::RaggedRow(1)));
+        assert_eq!(
+            parse(&[".x"]),
+            Err(PlatformError::InvalidTile {
+                row: 0,
+                col: 1,
+                tile: 'x'
+            })
+        );
+        assert_eq!(
+            parse(&[&".".repeat(129)]),
+            Err(PlatformError::TooLarge { rows: 1, cols: 129 })
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use crate::platform::{Direction, Platform};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProgramError {
+    Empty,
+    InvalidTilt(String),
+}
+
+// A platform as it stands right after the `step`th tilt, counting from 0 across passes
+#[derive(Debug, Clone)]
+pub struct TiltStep {
+    pub step: usize,
+    pub direction: Direction,
+    pub platform: Platform,
+}
+
+// A sequence of tilts that gets run over a platform as a whole, any number of times
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TiltProgram {
+    tilts: Vec<Direction>,
+}
+
+impl TiltProgram {
+    pub fn new(tilts: Vec<Direction>) -> Result<Self, ProgramError> {
+        if tilts.is_empty() {
+            return Err(ProgramError::Empty);
+        }
+
+        Ok(TiltProgram { tilts })
+    }
+
+    pub fn run_once(&self, platform: &mut Platform) {
+        for &direction in self.tilts.iter() {
+            platform.tilt(direction);
+        }
+    }
+
+    // Runs the program `passes` times. Once the platform comes back to a state it was in
+    // at the start of an earlier pass, only the leftover passes of that loop are walked.
+    pub fn run(&self, mut platform: Platform, passes: u64) -> Platform {
+        let mut seen: HashMap<Vec<u128>, u64> = HashMap::new();
+        let mut pass = 0;
+
+        while pass < passes {
+            if let Some(&first_seen) = seen.get(platform.get_round_rows()) {
+                let remaining = (passes - pass) % (pass - first_seen);
+
+                for _ in 0..remaining {
+                    self.run_once(&mut platform);
+                }
+
+                return platform;
+            }
+
+            seen.insert(platform.get_round_rows().to_vec(), pass);
+            self.run_once(&mut platform);
+            pass += 1;
+        }
+
+        platform
+    }
+
+    // Every intermediate platform, one per tilt, looping over the program forever
+    pub fn steps(&self, platform: Platform) -> impl Iterator<Item = TiltStep> + '_ {
+        self.tilts
+            .iter()
+            .cycle()
+            .enumerate()
+            .scan(platform, |platform, (step, &direction)| {
+                platform.tilt(direction);
+
+                Some(TiltStep {
+                    step,
+                    direction,
+                    platform: platform.clone(),
+                })
+            })
+    }
+}
+
+pub fn parse_direction(s: &str) -> Result<Direction, ProgramError> {
+    match s {
+        "N" => Ok(Direction::North),
+        "W" => Ok(Direction::West),
+        "S" => Ok(Direction::South),
+        "E" => Ok(Direction::East),
+        _ => Err(ProgramError::InvalidTilt(s.to_string())),
+    }
+}
+
+// Parses programs written as comma separated wall initials, e.g. `N,E,E,S`
+impl FromStr for TiltProgram {
+    type Err = ProgramError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Err(ProgramError::Empty);
+        }
+
+        TiltProgram::new(
+            s.split(',')
+                .map(|tilt| parse_direction(tilt.trim()))
+                .collect::<Result<Vec<_>, _>>()?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::platform::{Direction, Platform};
+    use crate::program::{ProgramError, TiltProgram};
+    use crate::tests::example_lines;
+
+    fn example_platform() -> Platform {
+        Platform::parse(&example_lines()).unwrap()
+    }
+
+    // Runs every pass in turn, without skipping ahead
+    fn run_slowly(program: &TiltProgram, mut platform: Platform, passes: u64) -> Platform {
+        for _ in 0..passes {
+            program.run_once(&mut platform);
+        }
+        platform
+    }
+
+    #[test]
+    fn parse_programs() {
+        let program: TiltProgram = "N, E,E,S".parse().unwrap();
+
+        assert_eq!(
+            program,
+            TiltProgram::new(vec![
+                Direction::North,
+                Direction::East,
+                Direction::East,
+                Direction::South
+            ])
+            .unwrap()
+        );
+        assert_eq!("".parse::<TiltProgram>(), Err(ProgramError::Empty));
+        assert_eq!(
+            "N,X".parse::<TiltProgram>(),
+            Err(ProgramError::InvalidTilt(String::from("X")))
+        );
+        assert_eq!(
+            "N,,S".parse::<TiltProgram>(),
+            Err(ProgramError::InvalidTilt(String::new()))
+        );
+    }
+
+    #[test]
+    fn skipping_repeated_states_matches_running_every_pass() {
+        for program_str in ["N,W,S,E", "N,E,E,S", "S", "W,N"] {
+            let program: TiltProgram = program_str.parse().unwrap();
+
+            for passes in [0, 1, 2, 7, 50, 123] {
+                assert_eq!(
+                    program.run(example_platform(), passes),
+                    run_slowly(&program, example_platform(), passes),
+                    "{} x{}",
+                    program_str,
+                    passes
+                );
+            }
+        }
+
+        let spin: TiltProgram = "N,W,S,E".parse().unwrap();
+
+        assert_eq!(spin.run(example_platform(), 1_000_000_000).north_load(), 64);
+    }
+
+    #[test]
+    fn intermediate_platforms() {
+        let program: TiltProgram = "N,E".parse().unwrap();
+        let steps: Vec<_> = program.steps(example_platform()).take(5).collect();
+
+        assert_eq!(
+            steps.iter().map(|s| s.direction).collect::<Vec<_>>(),
+            [
+                Direction::North,
+                Direction::East,
+                Direction::North,
+                Direction::East,
+                Direction::North
+            ]
+        );
+        assert_eq!(steps[0].platform.load(Direction::North), 136);
+        assert_eq!(
+            steps[3].platform,
+            run_slowly(&program, example_platform(), 2)
+        );
+        assert_eq!(steps[4].step, 4);
+    }
+}
@@ -0,0 +1,286 @@
+use std::fmt;
+use std::num::{NonZeroUsize, ParseIntError};
+use std::str::FromStr;
+
+pub const DEFAULT_NUM_BOXES: NonZeroUsize = NonZeroUsize::new(256).unwrap();
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lens {
+    pub label: String,
+    pub focal_length: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Insert(Lens),
+    Remove(String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CommandError {
+    // Neither `=<focal length>` nor a trailing `-`
+    MissingOperation(String),
+    InvalidLabel(String),
+    ParseError(ParseIntError),
+}
+
+impl From<ParseIntError> for CommandError {
+    fn from(err: ParseIntError) -> Self {
+        CommandError::ParseError(err)
+    }
+}
+
+impl Command {
+    pub fn get_label(&self) -> &str {
+        match self {
+            Command::Insert(lens) => &lens.label,
+            Command::Remove(label) => label,
+        }
+    }
+}
+
+// Writes the command back out as an initialization step, e.g. `rn=1` or `cm-`
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Command::Insert(lens) => write!(f, "{}={}", lens.label, lens.focal_length),
+            Command::Remove(label) => write!(f, "{}-", label),
+        }
+    }
+}
+
+impl FromStr for Command {
+    type Err = CommandError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let command = if let Some((label, focal_length)) = s.split_once('=') {
+            Command::Insert(Lens {
+                label: label.to_string(),
+                focal_length: focal_length.parse::<u32>()?,
+            })
+        } else if let Some(label) = s.strip_suffix('-') {
+            Command::Remove(label.to_string())
+        } else {
+            return Err(CommandError::MissingOperation(s.to_string()));
+        };
+
+        let label = command.get_label();
+
+        if label.is_empty() || label.contains(['=', '-']) {
+            return Err(CommandError::InvalidLabel(s.to_string()));
+        }
+
+        Ok(command)
+    }
+}
+
+// What a single step did: the box its label hashes to, and whether that box's lenses changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepOutcome {
+    pub box_num: usize,
+    pub changed: bool,
+}
+
+// The puzzle's HASH algorithm
+pub fn reindeer_hash(str: &str) -> u32 {
+    let mut current_value = 0;
+
+    for c in str.chars() {
+        let unicode_value = c as u32;
+
+        current_value += unicode_value;
+        current_value *= 17;
+        current_value %= 256;
+    }
+
+    current_value
+}
+
+pub struct LensLibrary {
+    boxes: Vec<Vec<Lens>>,
+    hash: fn(&str) -> u32,
+}
+
+impl LensLibrary {
+    pub fn new() -> Self {
+        LensLibrary::with_config(DEFAULT_NUM_BOXES, reindeer_hash)
+    }
+
+    // Labels go in box `hash(label) % num_boxes`
+    pub fn with_config(num_boxes: NonZeroUsize, hash: fn(&str) -> u32) -> Self {
+        LensLibrary {
+            boxes: vec![Vec::new(); num_boxes.get()],
+            hash,
+        }
+    }
+
+    pub fn num_boxes(&self) -> usize {
+        self.boxes.len()
+    }
+
+    pub fn box_for(&self, label: &str) -> usize {
+        (self.hash)(label) as usize % self.boxes.len()
+    }
+
+    pub fn get_box(&self, box_num: usize) -> &[Lens] {
+        &self.boxes[box_num]
+    }
+
+    pub fn apply(&mut self, command: &Command) -> StepOutcome {
+        let box_num = self.box_for(command.get_label());
+        let lenses = &mut self.boxes[box_num];
+        let existing = lenses.iter().position(|l| l.label == command.get_label());
+
+        let changed = match (command, existing) {
+            (Command::Insert(lens), Some(pos)) => {
+                let changed = lenses[pos] != *lens;
+                lenses[pos] = lens.clone();
+                changed
+            }
+            (Command::Insert(lens), None) => {
+                lenses.push(lens.clone());
+                true
+            }
+            (Command::Remove(_), Some(pos)) => {
+                lenses.remove(pos);
+                true
+            }
+            (Command::Remove(_), None) => false,
+        };
+
+        StepOutcome { box_num, changed }
+    }
+
+    // Applies every command in turn, handing the library to `inspect` after each one along
+    // with the step index and what that step did
+    pub fn replay(
+        &mut self,
+        commands: &[Command],
+        mut inspect: impl FnMut(usize, &Command, StepOutcome, &LensLibrary),
+    ) {
+        for (step, command) in commands.iter().enumerate() {
+            let outcome = self.apply(command);
+            inspect(step, command, outcome, self);
+        }
+    }
+
+    pub fn box_focusing_power(&self, box_num: usize) -> u64 {
+        self.boxes[box_num]
+            .iter()
+            .enumerate()
+            .map(|(slot, lens)| (box_num as u64 + 1) * (slot as u64 + 1) * lens.focal_length as u64)
+            .sum()
+    }
+
+    pub fn focusing_power(&self) -> u64 {
+        (0..self.boxes.len())
+            .map(|box_num| self.box_focusing_power(box_num))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::library::{reindeer_hash, Command, CommandError, Lens, LensLibrary, StepOutcome};
+    use std::num::NonZeroUsize;
+
+    const EXAMPLE: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
+
+    fn example_commands() -> Vec<Command> {
+        EXAMPLE
+            .split(',')
+            .map(|step| step.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(
+            "rn=1".parse::<Command>(),
+            Ok(Command::Insert(Lens {
+                label: String::from("rn"),
+                focal_length: 1
+            }))
+        );
+        assert_eq!(
+            "cm-".parse::<Command>(),
+            Ok(Command::Remove(String::from("cm")))
+        );
+        assert_eq!(
+            "cm".parse::<Command>(),
+            Err(CommandError::MissingOperation(String::from("cm")))
+        );
+        assert_eq!(
+            "=4".parse::<Command>(),
+            Err(CommandError::InvalidLabel(String::from("=4")))
+        );
+        assert_eq!(
+            "a-b-".parse::<Command>(),
+            Err(CommandError::InvalidLabel(String::from("a-b-")))
+        );
+        assert!(matches!(
+            "ab=x".parse::<Command>(),
+            Err(CommandError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn example_library() {
+        let mut library = LensLibrary::new();
+        let mut touched = Vec::new();
+
+        assert_eq!(reindeer_hash("HASH"), 52);
+
+        library.replay(&example_commands(), |_, _, outcome, library| {
+            touched.push((outcome, library.get_box(outcome.box_num).len()));
+        });
+
+        let step = |box_num, changed| StepOutcome { box_num, changed };
+        assert_eq!(
+            touched[..4],
+            [
+                (step(0, true), 1),
+                (step(0, false), 1),
+                (step(1, true), 1),
+                (step(0, true), 2)
+            ]
+        );
+        assert_eq!(library.get_box(3).len(), 3);
+        assert_eq!(library.box_focusing_power(0), 5);
+        assert_eq!(library.box_focusing_power(3), 28 + 40 + 72);
+        assert_eq!(library.focusing_power(), 145);
+    }
+
+    #[test]
+    fn custom_box_count_and_hash() {
+        let mut library =
+            LensLibrary::with_config(NonZeroUsize::new(4).unwrap(), |label| label.len() as u32);
+
+        for command in example_commands() {
+            library.apply(&command);
+        }
+
+        // Every label is two letters long, so they all share box 2
+        assert_eq!(library.num_boxes(), 4);
+        let labels: Vec<&str> = library
+            .get_box(2)
+            .iter()
+            .map(|l| l.label.as_str())
+            .collect();
+        assert_eq!(labels, vec!["rn", "cm", "ot", "ab", "pc"]);
+        assert_eq!(
+            library.focusing_power(),
+            3 * (1 + 2 * 2 + 3 * 7 + 4 * 5 + 5 * 6)
+        );
+    }
+
+    #[test]
+    fn unchanged_insert() {
+        let mut library = LensLibrary::new();
+        let insert: Command = "rn=1".parse().unwrap();
+
+        assert!(library.apply(&insert).changed);
+        assert!(!library.apply(&insert).changed);
+        assert!(library.apply(&"rn=2".parse().unwrap()).changed);
+    }
+}
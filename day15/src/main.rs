@@ -1,94 +1,96 @@
-use common::filereader;
-use std::ops::Index;
-
-struct Lense<'a> {
-    label: &'a str,
-    focal_length: u32,
+mod library;
+mod replay;
+
+use crate::library::{reindeer_hash, Command, LensLibrary};
+use crate::replay::{replay, BoxSelection, ReplayFormat};
+use common::{filereader, filewriter};
+use std::env;
+
+fn parse_commands(line: &str) -> Vec<Command> {
+    line.split(',')
+        .map(|step| match step.parse() {
+            Ok(command) => command,
+            Err(e) => panic!("Bad step {}: {:?}", step, e),
+        })
+        .collect()
 }
 
-enum LenseCommand<'a> {
-    Remove(&'a str),
-    Add(Lense<'a>),
+fn solve(lines: Vec<String>) -> u32 {
+    lines[0].split(",").map(reindeer_hash).sum()
 }
 
-fn reindeer_hash(str: &str) -> u32 {
-    let mut current_value = 0;
-
-    for c in str.chars() {
-        let unicode_value = c as u32;
+fn solve2(lines: Vec<String>) -> u64 {
+    let mut library = LensLibrary::new();
 
-        current_value += unicode_value;
-        current_value *= 17;
-        current_value %= 256;
+    for command in parse_commands(&lines[0]) {
+        library.apply(&command);
     }
 
-    current_value
+    library.focusing_power()
 }
 
-fn map_to_command(line: &str) -> LenseCommand {
-    if line.contains("=") {
-        let splitted: Vec<String> = line.split("=").map(|s| s.to_string()).collect();
+fn write_replay(lines: Vec<String>, format: ReplayFormat, selection: BoxSelection, path: &str) {
+    let output = replay(
+        &mut LensLibrary::new(),
+        &parse_commands(&lines[0]),
+        format,
+        selection,
+    );
 
-        LenseCommand::Add(Lense {
-            label: &line[..line.chars().position(|c| c == '=').unwrap()],
-            focal_length: splitted[1].parse::<u32>().unwrap(),
-        })
-    } else {
-        LenseCommand::Remove(&line[..line.len() - 1])
+    if let Err(e) = filewriter::write_file(path, output) {
+        panic!("{}", e);
     }
 }
 
-fn solve(lines: Vec<String>) -> u32 {
-    println!("{}", reindeer_hash("qp"));
-    lines[0].split(",").map(|s| reindeer_hash(s)).sum()
-}
-
-fn solve2(lines: Vec<String>) -> u32 {
-    let mut boxes: Vec<Vec<Lense>> = (0..256).map(|_| Vec::new()).collect();
-
-    let lense_commands: Vec<LenseCommand> =
-        lines[0].split(",").map(|str| map_to_command(str)).collect();
-
-    for command in lense_commands {
-        match command {
-            LenseCommand::Add(lense) => {
-                let box_num = reindeer_hash(lense.label) as usize;
-
-                if let Some(pos) = boxes[box_num].iter().position(|l| l.label == lense.label) {
-                    boxes[box_num][pos] = lense;
-                } else {
-                    boxes[box_num].push(lense);
-                }
-            }
-            LenseCommand::Remove(label) => {
-                let box_num = reindeer_hash(label) as usize;
-
-                if let Some(pos) = boxes[box_num].iter().position(|l| l.label == label) {
-                    boxes[box_num].remove(pos);
-                }
-            }
-        }
-    }
-
-    boxes
-        .iter()
-        .enumerate()
-        .map(|(i, lenses)| {
-            lenses
-                .iter()
-                .enumerate()
-                .map(|(l_index, lense)| (i + 1) as u32 * (l_index + 1) as u32 * lense.focal_length)
-                .sum::<u32>()
-        })
-        .sum()
+// `--replay [text|jsonl] [--changed]` also dumps the library after every step, to
+// ./day15/resources/replay.txt or replay.jsonl; `--changed` only shows the box each step altered
+fn replay_options() -> Option<(ReplayFormat, BoxSelection, String)> {
+    let args: Vec<String> = env::args().collect();
+    let flag = args.iter().position(|arg| arg == "--replay")?;
+
+    let format = match args.get(flag + 1).map(|arg| arg.as_str()) {
+        Some("jsonl") => ReplayFormat::Jsonl,
+        Some("text") | None => ReplayFormat::Text,
+        Some(arg) if arg.starts_with("--") => ReplayFormat::Text,
+        Some(arg) => panic!("Unknown replay format {}", arg),
+    };
+    let selection = if args.iter().any(|arg| arg == "--changed") {
+        BoxSelection::Changed
+    } else {
+        BoxSelection::NonEmpty
+    };
+    let path = match format {
+        ReplayFormat::Text => "./day15/resources/replay.txt",
+        ReplayFormat::Jsonl => "./day15/resources/replay.jsonl",
+    };
+
+    Some((format, selection, String::from(path)))
 }
 
 fn main() {
     match filereader::read_file("./day15/resources/input.txt") {
         Ok(lines) => {
+            if let Some((format, selection, path)) = replay_options() {
+                write_replay(lines.clone(), format, selection, &path);
+            }
             let result = solve2(lines);
             println!("{:?}", result);
         }
         Err(e) => panic!("{}", e),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{solve, solve2};
+
+    #[test]
+    fn example_sequence() {
+        let lines = vec![String::from(
+            "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7",
+        )];
+
+        assert_eq!(solve(lines.clone()), 1320);
+        assert_eq!(solve2(lines), 145);
+    }
+}
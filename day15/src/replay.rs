@@ -0,0 +1,213 @@
+use crate::library::{Command, Lens, LensLibrary};
+use common::json::json_string;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ReplayFormat {
+    // The puzzle's dump: `After "rn=1":`, one `Box 0: [rn 1]` line per box, then a blank line
+    Text,
+    // One JSON object per step
+    Jsonl,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum BoxSelection {
+    // Every box holding at least one lens, as the puzzle shows it
+    NonEmpty,
+    // Only the box a step actually altered, even if that left it empty
+    Changed,
+}
+
+pub fn replay(
+    library: &mut LensLibrary,
+    commands: &[Command],
+    format: ReplayFormat,
+    selection: BoxSelection,
+) -> Vec<String> {
+    let mut output = Vec::new();
+
+    library.replay(commands, |step, command, outcome, library| {
+        let boxes: Vec<usize> = match selection {
+            BoxSelection::NonEmpty => (0..library.num_boxes())
+                .filter(|&idx| !library.get_box(idx).is_empty())
+                .collect(),
+            BoxSelection::Changed if outcome.changed => vec![outcome.box_num],
+            BoxSelection::Changed => vec![],
+        };
+
+        match format {
+            ReplayFormat::Text => {
+                output.push(format!("After \"{}\":", command));
+                output.extend(
+                    boxes
+                        .iter()
+                        .map(|&idx| format_box_text(idx, library.get_box(idx))),
+                );
+                output.push(String::new());
+            }
+            ReplayFormat::Jsonl => {
+                let boxes_json: Vec<String> = boxes
+                    .iter()
+                    .map(|&idx| format_box_json(idx, library.get_box(idx)))
+                    .collect();
+
+                output.push(format!(
+                    "{{\"step\":{},\"command\":{},\"boxes\":[{}]}}",
+                    step,
+                    json_string(&command.to_string()),
+                    boxes_json.join(",")
+                ));
+            }
+        }
+    });
+
+    output
+}
+
+fn format_box_text(box_num: usize, lenses: &[Lens]) -> String {
+    let mut line = format!("Box {}:", box_num);
+
+    for lens in lenses {
+        line.push_str(&format!(" [{} {}]", lens.label, lens.focal_length));
+    }
+
+    line
+}
+
+fn format_box_json(box_num: usize, lenses: &[Lens]) -> String {
+    let lenses_json: Vec<String> = lenses
+        .iter()
+        .map(|lens| {
+            format!(
+                "{{\"label\":{},\"focal_length\":{}}}",
+                json_string(&lens.label),
+                lens.focal_length
+            )
+        })
+        .collect();
+
+    format!(
+        "{{\"box\":{},\"lenses\":[{}]}}",
+        box_num,
+        lenses_json.join(",")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::library::{Command, LensLibrary};
+    use crate::replay::{replay, BoxSelection, ReplayFormat};
+
+    fn example_commands() -> Vec<Command> {
+        "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7"
+            .split(',')
+            .map(|step| step.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn puzzle_text_dump() {
+        let output = replay(
+            &mut LensLibrary::new(),
+            &example_commands(),
+            ReplayFormat::Text,
+            BoxSelection::NonEmpty,
+        );
+
+        let expected = [
+            "After \"rn=1\":",
+            "Box 0: [rn 1]",
+            "",
+            "After \"cm-\":",
+            "Box 0: [rn 1]",
+            "",
+            "After \"qp=3\":",
+            "Box 0: [rn 1]",
+            "Box 1: [qp 3]",
+            "",
+            "After \"cm=2\":",
+            "Box 0: [rn 1] [cm 2]",
+            "Box 1: [qp 3]",
+            "",
+            "After \"qp-\":",
+            "Box 0: [rn 1] [cm 2]",
+            "",
+            "After \"pc=4\":",
+            "Box 0: [rn 1] [cm 2]",
+            "Box 3: [pc 4]",
+            "",
+            "After \"ot=9\":",
+            "Box 0: [rn 1] [cm 2]",
+            "Box 3: [pc 4] [ot 9]",
+            "",
+            "After \"ab=5\":",
+            "Box 0: [rn 1] [cm 2]",
+            "Box 3: [pc 4] [ot 9] [ab 5]",
+            "",
+            "After \"pc-\":",
+            "Box 0: [rn 1] [cm 2]",
+            "Box 3: [ot 9] [ab 5]",
+            "",
+            "After \"pc=6\":",
+            "Box 0: [rn 1] [cm 2]",
+            "Box 3: [ot 9] [ab 5] [pc 6]",
+            "",
+            "After \"ot=7\":",
+            "Box 0: [rn 1] [cm 2]",
+            "Box 3: [ot 7] [ab 5] [pc 6]",
+            "",
+        ];
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn changed_boxes_only() {
+        let commands = example_commands();
+
+        let text = replay(
+            &mut LensLibrary::new(),
+            &commands[..5],
+            ReplayFormat::Text,
+            BoxSelection::Changed,
+        );
+
+        // `cm-` finds nothing to remove, and `qp-` empties box 1
+        assert_eq!(
+            text,
+            [
+                "After \"rn=1\":",
+                "Box 0: [rn 1]",
+                "",
+                "After \"cm-\":",
+                "",
+                "After \"qp=3\":",
+                "Box 1: [qp 3]",
+                "",
+                "After \"cm=2\":",
+                "Box 0: [rn 1] [cm 2]",
+                "",
+                "After \"qp-\":",
+                "Box 1:",
+                "",
+            ]
+        );
+
+        let jsonl = replay(
+            &mut LensLibrary::new(),
+            &commands[..5],
+            ReplayFormat::Jsonl,
+            BoxSelection::Changed,
+        );
+
+        assert_eq!(
+            jsonl,
+            [
+                "{\"step\":0,\"command\":\"rn=1\",\"boxes\":[{\"box\":0,\"lenses\":[{\"label\":\"rn\",\"focal_length\":1}]}]}",
+                "{\"step\":1,\"command\":\"cm-\",\"boxes\":[]}",
+                "{\"step\":2,\"command\":\"qp=3\",\"boxes\":[{\"box\":1,\"lenses\":[{\"label\":\"qp\",\"focal_length\":3}]}]}",
+                "{\"step\":3,\"command\":\"cm=2\",\"boxes\":[{\"box\":0,\"lenses\":[{\"label\":\"rn\",\"focal_length\":1},{\"label\":\"cm\",\"focal_length\":2}]}]}",
+                "{\"step\":4,\"command\":\"qp-\",\"boxes\":[{\"box\":1,\"lenses\":[]}]}",
+            ]
+        );
+    }
+}
@@ -0,0 +1,242 @@
+pub use common::geometry::Direction;
+use common::geometry::{
+    boundary_points, double_area, pick_interior_points, segment_intersection, Point2,
+    SegmentIntersection,
+};
+
+pub fn direction_from_char(c: char) -> Option<Direction> {
+    match c {
+        'U' => Some(Direction::Up),
+        'L' => Some(Direction::Left),
+        'D' => Some(Direction::Down),
+        'R' => Some(Direction::Right),
+        _ => None,
+    }
+}
+
+pub fn direction_from_digit(d: u8) -> Option<Direction> {
+    match d {
+        3 => Some(Direction::Up),
+        2 => Some(Direction::Left),
+        1 => Some(Direction::Down),
+        0 => Some(Direction::Right),
+        _ => None,
+    }
+}
+
+pub type Point = Point2<i64>;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DigPlanError {
+    Empty,
+    ZeroLengthTrench(usize),
+    // The trench at this index runs off the edge of the i64 coordinate space
+    TrenchTooLong(usize),
+    NotClosed(Point),
+    // The trenches at the two indices fold back along each other
+    Overlapping(usize, usize),
+    // The trenches at the two indices cross or touch somewhere other than a shared corner
+    SelfIntersecting(usize, usize),
+}
+
+#[derive(Debug)]
+pub struct DigPlan {
+    instructions: Vec<(Direction, u64)>,
+    vertices: Vec<Point>,
+}
+
+impl DigPlan {
+    pub fn new(instructions: Vec<(Direction, u64)>) -> Result<Self, DigPlanError> {
+        if instructions.is_empty() {
+            return Err(DigPlanError::Empty);
+        }
+
+        if let Some(idx) = instructions.iter().position(|&(_, steps)| steps == 0) {
+            return Err(DigPlanError::ZeroLengthTrench(idx));
+        }
+
+        let mut vertices = Vec::with_capacity(instructions.len());
+        let mut curr_point = Point::new(0, 0);
+
+        for (idx, &(direction, steps)) in instructions.iter().enumerate() {
+            vertices.push(curr_point);
+            curr_point = i64::try_from(steps)
+                .ok()
+                .and_then(|steps| curr_point.step(direction, steps))
+                .ok_or(DigPlanError::TrenchTooLong(idx))?;
+        }
+
+        if curr_point != vertices[0] {
+            return Err(DigPlanError::NotClosed(curr_point));
+        }
+
+        let plan = DigPlan {
+            instructions,
+            vertices,
+        };
+        plan.validate_trenches()?;
+
+        Ok(plan)
+    }
+
+    pub fn get_vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    pub fn get_segment(&self, idx: usize) -> (Point, Point) {
+        (
+            self.vertices[idx],
+            self.vertices[(idx + 1) % self.vertices.len()],
+        )
+    }
+
+    pub fn get_boundary_count(&self) -> u128 {
+        boundary_points(&self.vertices)
+    }
+
+    // Validation rules out folded or crossing trenches, so Pick's theorem always applies
+    pub fn get_interior_count(&self) -> u128 {
+        match pick_interior_points(double_area(&self.vertices), self.get_boundary_count()) {
+            Some(interior) => interior,
+            None => panic!("Dig plan is not a simple polygon: {:?}", self.vertices),
+        }
+    }
+
+    pub fn get_total_area(&self) -> u128 {
+        self.get_boundary_count() + self.get_interior_count()
+    }
+
+    fn validate_trenches(&self) -> Result<(), DigPlanError> {
+        let len = self.instructions.len();
+
+        for i in 0..len {
+            let next = (i + 1) % len;
+
+            if self.instructions[i].0.opposite() == self.instructions[next].0 {
+                return Err(DigPlanError::Overlapping(i.min(next), i.max(next)));
+            }
+        }
+
+        // Neighbouring trenches always share exactly one corner, which the check above
+        // already covers, so only non-adjacent pairs need to stay fully apart
+        for i in 0..len {
+            for j in (i + 2)..len {
+                if i == 0 && j == len - 1 {
+                    continue;
+                }
+
+                match segment_intersection(self.get_segment(i), self.get_segment(j)) {
+                    SegmentIntersection::None => {}
+                    SegmentIntersection::Overlap(_, _) => {
+                        return Err(DigPlanError::Overlapping(i, j))
+                    }
+                    _ => return Err(DigPlanError::SelfIntersecting(i, j)),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dig_plan::{direction_from_char, DigPlan, DigPlanError, Direction, Point};
+
+    fn plan_from(instructions: &[(char, u64)]) -> Result<DigPlan, DigPlanError> {
+        DigPlan::new(
+            instructions
+                .iter()
+                .map(|&(c, steps)| (direction_from_char(c).unwrap(), steps))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn example_plan() {
+        let plan = plan_from(&[
+            ('R', 6),
+            ('D', 5),
+            ('L', 2),
+            ('D', 2),
+            ('R', 2),
+            ('D', 2),
+            ('L', 5),
+            ('U', 2),
+            ('L', 1),
+            ('U', 2),
+            ('R', 2),
+            ('U', 3),
+            ('L', 2),
+            ('U', 2),
+        ])
+        .unwrap();
+
+        assert_eq!(plan.get_vertices().len(), 14);
+        assert_eq!(plan.get_vertices()[1], Point::new(0, 6));
+        assert_eq!(plan.get_boundary_count(), 38);
+        assert_eq!(plan.get_interior_count(), 24);
+        assert_eq!(plan.get_total_area(), 62);
+    }
+
+    #[test]
+    fn rejects_open_plan() {
+        assert_eq!(
+            plan_from(&[('R', 2), ('D', 2), ('L', 2)]).unwrap_err(),
+            DigPlanError::NotClosed(Point::new(2, 0))
+        );
+    }
+
+    #[test]
+    fn rejects_degenerate_plans() {
+        assert_eq!(plan_from(&[]).unwrap_err(), DigPlanError::Empty);
+        assert_eq!(
+            plan_from(&[('R', 2), ('D', 0), ('L', 2)]).unwrap_err(),
+            DigPlanError::ZeroLengthTrench(1)
+        );
+        assert_eq!(
+            plan_from(&[('R', u64::MAX), ('L', u64::MAX)]).unwrap_err(),
+            DigPlanError::TrenchTooLong(0)
+        );
+        assert_eq!(
+            DigPlan::new(vec![
+                (Direction::Right, i64::MAX as u64),
+                (Direction::Right, 1),
+            ])
+            .unwrap_err(),
+            DigPlanError::TrenchTooLong(1)
+        );
+        assert_eq!(
+            plan_from(&[('R', 2), ('L', 2)]).unwrap_err(),
+            DigPlanError::Overlapping(0, 1)
+        );
+    }
+
+    #[test]
+    fn rejects_crossing_trenches() {
+        // The trench heading up cuts straight through the first one
+        assert_eq!(
+            plan_from(&[('R', 2), ('D', 2), ('L', 1), ('U', 3), ('L', 1), ('D', 1)]).unwrap_err(),
+            DigPlanError::SelfIntersecting(0, 3)
+        );
+    }
+
+    #[test]
+    fn rejects_overlapping_trenches() {
+        // The fifth trench runs back along part of the first
+        assert_eq!(
+            plan_from(&[
+                ('R', 4),
+                ('D', 2),
+                ('R', 2),
+                ('U', 2),
+                ('L', 4),
+                ('U', 1),
+                ('L', 2),
+                ('D', 1),
+            ])
+            .unwrap_err(),
+            DigPlanError::Overlapping(0, 4)
+        );
+    }
+}
@@ -1,137 +1,180 @@
+mod dig_plan;
+mod svg;
+
+use crate::dig_plan::{direction_from_char, direction_from_digit, DigPlan, Direction};
 use common::filereader;
 use common::filewriter::write_file;
-use common::formatting::format_grid;
 use regex::Regex;
-use std::ops::{Add, Div, Sub};
-
-#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Copy, Clone)]
-enum Direction {
-    Up,
-    Left,
-    Down,
-    Right,
+use std::env;
+use std::num::ParseIntError;
+
+#[derive(Debug, PartialEq, Eq)]
+enum LineError {
+    // Missing the direction, the distance or the `(#rrggbb)` colour
+    Malformed(String),
+    BadDirection(String),
+    ParseError(ParseIntError),
 }
 
-impl Direction {
-    fn from_char(c: char) -> Self {
-        match c {
-            'U' => Direction::Up,
-            'L' => Direction::Left,
-            'D' => Direction::Down,
-            'R' => Direction::Right,
-            _ => unreachable!(),
-        }
-    }
-
-    fn from_digit(d: u8) -> Self {
-        match d {
-            3 => Direction::Up,
-            2 => Direction::Left,
-            1 => Direction::Down,
-            0 => Direction::Right,
-            _ => unreachable!(),
-        }
-    }
-
-    fn to_unit_coord(&self) -> (isize, isize) {
-        match self {
-            Direction::Up => (-1, 0),
-            Direction::Left => (0, -1),
-            Direction::Down => (1, 0),
-            Direction::Right => (0, 1),
-        }
+impl From<ParseIntError> for LineError {
+    fn from(err: ParseIntError) -> Self {
+        LineError::ParseError(err)
     }
 }
 
-#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Copy, Clone)]
-struct Point {
-    x: i64,
-    y: i64,
-}
+type Instruction = (Direction, u64);
 
-impl Point {
-    fn new(x: i64, y: i64) -> Self {
-        Point { x, y }
-    }
+fn parse_line(line: String) -> Result<Instruction, LineError> {
+    let splitted = line.split_whitespace().collect::<Vec<_>>();
 
-    fn move_to(&self, direction: Direction, steps: u64) -> Point {
-        let (dir_row, dir_col) = direction.to_unit_coord();
+    let (Some(&direction_str), Some(&distance_str)) = (splitted.first(), splitted.get(1)) else {
+        return Err(LineError::Malformed(line));
+    };
 
-        Point {
-            x: self.x + (dir_row as i64 * steps as i64),
-            y: self.y + (dir_col as i64 * steps as i64),
-        }
-    }
+    let mut direction_chars = direction_str.chars();
+    let direction = match (direction_chars.next(), direction_chars.next()) {
+        (Some(c), None) => direction_from_char(c),
+        _ => None,
+    };
 
-    fn get_area(points: Vec<Point>) -> u64 {
-        let mut area = 0_i64;
-        for i in 0..points.len() {
-            let curr = points[i];
-            let next = points[(i + 1) % points.len()];
-            area += (curr.y * next.x) - (curr.x * next.y);
-        }
-        return area.abs().div(2) as u64;
-    }
+    Ok((
+        direction.ok_or_else(|| LineError::BadDirection(direction_str.to_string()))?,
+        distance_str.parse::<u64>()?,
+    ))
 }
 
-fn parse_line(line: String) -> (Direction, u64) {
-    let splitted = line.split_whitespace().collect::<Vec<_>>();
+fn parse_line_hex(line: String) -> Result<Instruction, LineError> {
+    let reg = Regex::new(r"\(#([0-9a-f]{5})([0-9a-f])\)").unwrap();
+    let Some(caps) = reg.captures(line.as_str()) else {
+        return Err(LineError::Malformed(line));
+    };
+
+    let distance_hex = caps.get(1).unwrap().as_str();
+    let direction_hex = caps.get(2).unwrap().as_str();
+    let direction = direction_from_digit(u8::from_str_radix(direction_hex, 16)?)
+        .ok_or_else(|| LineError::BadDirection(direction_hex.to_string()))?;
 
-    (
-        Direction::from_char(splitted[0].chars().find(|_| true).unwrap()),
-        splitted[1].parse::<u64>().unwrap(),
-    )
+    Ok((direction, u64::from_str_radix(distance_hex, 16)?))
 }
 
-fn parse_line_hex(line: String) -> (Direction, u64) {
-    let reg = Regex::new(r"\(#([a-z0-9]{5})(\d)\)").unwrap();
-    if let Some(caps) = reg.captures(line.as_str()) {
-        let distance_hex = caps.get(1).unwrap().as_str();
-        let direction = caps.get(2).unwrap().as_str().parse::<u8>().unwrap();
-        return (
-            Direction::from_digit(direction),
-            u64::from_str_radix(distance_hex, 16).unwrap(),
-        );
+fn parse_colour(reg: &Regex, line: &str) -> Result<String, LineError> {
+    match reg.captures(line) {
+        Some(caps) => Ok(caps.get(1).unwrap().as_str().to_string()),
+        None => Err(LineError::Malformed(line.to_string())),
     }
-
-    unreachable!()
 }
 
-fn get_total_area_for_instructions(instructions: Vec<(Direction, u64)>) -> u64 {
-    let mut points = vec![];
-    let mut curr_point = Point::new(0, 0);
+fn parse_lines<T>(lines: &[String], parse: impl Fn(&String) -> Result<T, LineError>) -> Vec<T> {
+    lines
+        .iter()
+        .map(|line| match parse(line) {
+            Ok(parsed) => parsed,
+            Err(e) => panic!("Bad dig plan line {}: {:?}", line, e),
+        })
+        .collect()
+}
 
-    for &(direction, steps) in instructions.iter() {
-        points.push(curr_point);
-        curr_point = curr_point.move_to(direction, steps);
+fn write_plan_svg(
+    lines: &[String],
+    parse: fn(String) -> Result<Instruction, LineError>,
+    path: &str,
+) {
+    let instructions = parse_lines(lines, |line| parse(line.clone()));
+    let colour_reg = Regex::new(r"\((#[a-f0-9]{6})\)").unwrap();
+    let colours = parse_lines(lines, |line| parse_colour(&colour_reg, line));
+
+    match DigPlan::new(instructions) {
+        Ok(plan) => {
+            if let Err(e) = write_file(path, svg::render(&plan, &colours, 1000.0)) {
+                panic!("{}", e)
+            }
+        }
+        Err(e) => panic!("Invalid dig plan: {:?}", e),
     }
+}
 
-    points.push(curr_point);
+fn get_total_area_for_instructions(instructions: Vec<Instruction>) -> u128 {
+    match DigPlan::new(instructions) {
+        Ok(plan) => plan.get_total_area(),
+        Err(e) => panic!("Invalid dig plan: {:?}", e),
+    }
+}
 
-    let border_points = instructions.iter().fold(0, |acc, &(_, count)| acc + count);
-    let inside_area = Point::get_area(points.clone()) - border_points / 2 + 1;
+fn solve(lines: Vec<String>) -> u128 {
+    let instructions = parse_lines(&lines, |line| parse_line(line.clone()));
 
-    border_points + inside_area
+    get_total_area_for_instructions(instructions)
 }
 
-fn solve(lines: Vec<String>) -> u64 {
-    let instructions: Vec<_> = lines.into_iter().map(|line| parse_line(line)).collect();
+fn solve2(lines: Vec<String>) -> u128 {
+    let instructions = parse_lines(&lines, |line| parse_line_hex(line.clone()));
 
     get_total_area_for_instructions(instructions)
 }
 
-fn solve2(lines: Vec<String>) -> u64 {
-    let instructions: Vec<_> = lines.into_iter().map(|line| parse_line_hex(line)).collect();
+// `--svg [path]` also renders the part 2 dig plan, to ./day18/resources/output.svg by default
+fn svg_output_path() -> Option<String> {
+    let mut args = env::args().skip_while(|arg| arg != "--svg");
+    args.next()?;
 
-    get_total_area_for_instructions(instructions)
+    match args.next() {
+        Some(path) if path.ends_with(".svg") => Some(path),
+        Some(path) => panic!("Bad --svg path {}: expected a file ending in .svg", path),
+        None => Some(String::from("./day18/resources/output.svg")),
+    }
 }
 
 fn main() {
     match filereader::read_file("./day18/resources/input.txt") {
         Ok(lines) => {
+            if let Some(path) = svg_output_path() {
+                write_plan_svg(&lines, parse_line_hex, &path);
+            }
             let result = solve2(lines);
             println!("{:?}", result);
         }
         Err(e) => panic!("{}", e),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::dig_plan::Direction;
+    use crate::{parse_line, parse_line_hex, LineError};
+
+    #[test]
+    fn parse_lines() {
+        let line = String::from("R 6 (#70c710)");
+
+        assert_eq!(parse_line(line.clone()), Ok((Direction::Right, 6)));
+        assert_eq!(parse_line_hex(line), Ok((Direction::Right, 461937)));
+    }
+
+    #[test]
+    fn reports_bad_lines() {
+        let parse = |line: &str| parse_line(line.to_string());
+        let parse_hex = |line: &str| parse_line_hex(line.to_string());
+
+        assert_eq!(
+            parse("X 6 (#70c710)"),
+            Err(LineError::BadDirection(String::from("X")))
+        );
+        assert_eq!(
+            parse("RD 6 (#70c710)"),
+            Err(LineError::BadDirection(String::from("RD")))
+        );
+        assert_eq!(
+            parse("R x (#70c710)"),
+            Err(LineError::ParseError("x".parse::<u64>().unwrap_err()))
+        );
+        assert_eq!(parse("R"), Err(LineError::Malformed(String::from("R"))));
+        assert_eq!(
+            parse_hex("R 6 (#70c714)"),
+            Err(LineError::BadDirection(String::from("4")))
+        );
+        assert_eq!(
+            parse_hex("R 6 (#70cz10)"),
+            Err(LineError::Malformed(String::from("R 6 (#70cz10)")))
+        );
+    }
+}
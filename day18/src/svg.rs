@@ -0,0 +1,129 @@
+use crate::dig_plan::{DigPlan, Point};
+
+const MARGIN: f64 = 10.0;
+const INTERIOR_FILL: &str = "#5c4033";
+const DEFAULT_STROKE: &str = "#000000";
+
+struct Viewport {
+    min: Point,
+    scale: f64,
+    stroke_width: f64,
+}
+
+impl Viewport {
+    // Picks a single scale so the longer side of the plan fills `size` pixels. Part 2
+    // plans span millions of metres, so the trench width is clamped to stay visible.
+    fn fit(vertices: &[Point], size: f64) -> Self {
+        let min = Point::new(
+            vertices.iter().map(|p| p.x).min().unwrap_or(0),
+            vertices.iter().map(|p| p.y).min().unwrap_or(0),
+        );
+        let max = Point::new(
+            vertices.iter().map(|p| p.x).max().unwrap_or(0),
+            vertices.iter().map(|p| p.y).max().unwrap_or(0),
+        );
+
+        let extent = (max.x - min.x).max(max.y - min.y).max(1) as f64;
+        let scale = (size - 2.0 * MARGIN).max(1.0) / extent;
+
+        Viewport {
+            min,
+            scale,
+            stroke_width: scale.clamp(1.0, MARGIN),
+        }
+    }
+
+    // Rows run down the page and columns across, matching how the plan reads as a grid
+    fn project(&self, point: Point) -> (f64, f64) {
+        (
+            MARGIN + (point.y - self.min.y) as f64 * self.scale,
+            MARGIN + (point.x - self.min.x) as f64 * self.scale,
+        )
+    }
+}
+
+pub fn render(plan: &DigPlan, colours: &[String], size: f64) -> Vec<String> {
+    let vertices = plan.get_vertices();
+    let viewport = Viewport::fit(vertices, size);
+
+    let mut svg = vec![format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{size}\" height=\"{size}\" viewBox=\"0 0 {size} {size}\">"
+    )];
+
+    let polygon_points = vertices
+        .iter()
+        .map(|&p| {
+            let (x, y) = viewport.project(p);
+            format!("{x:.2},{y:.2}")
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    svg.push(format!(
+        "  <polygon points=\"{polygon_points}\" fill=\"{INTERIOR_FILL}\" stroke=\"none\"/>"
+    ));
+
+    for idx in 0..vertices.len() {
+        let (start, end) = plan.get_segment(idx);
+        let (x1, y1) = viewport.project(start);
+        let (x2, y2) = viewport.project(end);
+        let colour = colours.get(idx).map_or(DEFAULT_STROKE, |c| c.as_str());
+
+        svg.push(format!(
+            "  <line x1=\"{x1:.2}\" y1=\"{y1:.2}\" x2=\"{x2:.2}\" y2=\"{y2:.2}\" stroke=\"{colour}\" stroke-width=\"{:.2}\" stroke-linecap=\"square\"/>",
+            viewport.stroke_width
+        ));
+    }
+
+    svg.push(String::from("</svg>"));
+
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dig_plan::{DigPlan, Direction};
+    use crate::svg::render;
+
+    #[test]
+    fn render_square() {
+        let plan = DigPlan::new(vec![
+            (Direction::Right, 4),
+            (Direction::Down, 2),
+            (Direction::Left, 4),
+            (Direction::Up, 2),
+        ])
+        .unwrap();
+        let colours = vec![String::from("#70c710"), String::from("#0dc571")];
+
+        let svg = render(&plan, &colours, 100.0);
+
+        assert_eq!(svg.len(), 7);
+        assert_eq!(
+            svg[1],
+            "  <polygon points=\"10.00,10.00 90.00,10.00 90.00,50.00 10.00,50.00\" fill=\"#5c4033\" stroke=\"none\"/>"
+        );
+        assert!(svg[2].contains("x1=\"10.00\" y1=\"10.00\" x2=\"90.00\" y2=\"10.00\""));
+        assert!(svg[2].contains("stroke=\"#70c710\" stroke-width=\"10.00\""));
+        assert!(svg[3].contains("stroke=\"#0dc571\""));
+        // Missing colours fall back to black instead of failing the export
+        assert!(svg[4].contains("stroke=\"#000000\""));
+        assert_eq!(svg[6], "</svg>");
+    }
+
+    #[test]
+    fn render_scales_huge_plans_down() {
+        let plan = DigPlan::new(vec![
+            (Direction::Right, 10_000_000),
+            (Direction::Down, 5_000_000),
+            (Direction::Left, 10_000_000),
+            (Direction::Up, 5_000_000),
+        ])
+        .unwrap();
+
+        let svg = render(&plan, &[], 820.0);
+
+        assert!(svg[1].contains("810.00,410.00"));
+        assert!(svg[2].contains("stroke-width=\"1.00\""));
+    }
+}
@@ -0,0 +1,452 @@
+use crate::hyper_rect::HyperRect;
+use crate::workflow::{
+    workflows_by_name, Comparison, Condition, Part, UndefinedWorkflow, Workflow, ENTRY_WORKFLOW,
+};
+use std::collections::{HashMap, HashSet};
+
+pub(crate) type NodeId = usize;
+
+const ACCEPT: NodeId = 0;
+const REJECT: NodeId = 1;
+
+#[derive(Hash, Clone, Copy, Eq, PartialEq, Debug)]
+pub(crate) enum Node {
+    Accept,
+    Reject,
+    Branch {
+        condition: Comparison,
+        if_true: NodeId,
+        if_false: NodeId,
+    },
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) enum CompileError {
+    UndefinedWorkflow(UndefinedWorkflow),
+    Cycle(Vec<String>),
+}
+
+// Every workflow reachable from `in` flattened into one graph of one-sided comparisons.
+// Identical subgraphs are shared, so there is exactly one `Accept` and one `Reject`
+// leaf, and a branch whose two sides lead to the same node is never emitted.
+#[derive(Debug)]
+pub(crate) struct DecisionDag {
+    nodes: Vec<Node>,
+    root: NodeId,
+    // Comparisons were pruned against these, so parts outside them can't be judged
+    bounds: HyperRect,
+}
+
+impl DecisionDag {
+    pub fn compile(workflows: &[Workflow], bounds: HyperRect) -> Result<Self, CompileError> {
+        let workflows_by_name = workflows_by_name(workflows);
+
+        Self::validate(&workflows_by_name)?;
+
+        let mut compiler = Compiler {
+            workflows_by_name,
+            nodes: vec![Node::Accept, Node::Reject],
+            interned: HashMap::from([(Node::Accept, ACCEPT), (Node::Reject, REJECT)]),
+            compiled: HashMap::new(),
+        };
+        let root = compiler.compile(&Next::Target(ENTRY_WORKFLOW), bounds.clone());
+
+        Ok(DecisionDag {
+            nodes: compiler.nodes,
+            root,
+            bounds,
+        })
+    }
+
+    // Rejects references to workflows that don't exist, and any loop between workflows,
+    // before compiling (which would otherwise recurse forever)
+    fn validate(workflows_by_name: &HashMap<&str, &Workflow>) -> Result<(), CompileError> {
+        if !workflows_by_name.contains_key(ENTRY_WORKFLOW) {
+            return Err(CompileError::UndefinedWorkflow(UndefinedWorkflow {
+                from: String::new(),
+                name: String::from(ENTRY_WORKFLOW),
+            }));
+        }
+
+        let mut finished = HashSet::new();
+        let mut names: Vec<&&str> = workflows_by_name.keys().collect();
+        names.sort();
+
+        for name in names {
+            let mut path = Vec::new();
+            Self::visit(name, workflows_by_name, &mut path, &mut finished)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit<'a>(
+        name: &'a str,
+        workflows_by_name: &HashMap<&'a str, &'a Workflow>,
+        path: &mut Vec<&'a str>,
+        finished: &mut HashSet<&'a str>,
+    ) -> Result<(), CompileError> {
+        if finished.contains(name) {
+            return Ok(());
+        }
+
+        if let Some(start) = path.iter().position(|&n| n == name) {
+            let mut cycle: Vec<String> = path[start..].iter().map(|n| n.to_string()).collect();
+            cycle.push(name.to_string());
+            return Err(CompileError::Cycle(cycle));
+        }
+
+        let workflow = workflows_by_name[name];
+        path.push(name);
+
+        let destinations = workflow
+            .instructions
+            .iter()
+            .map(|instruction| instruction.destination.as_str())
+            .chain(std::iter::once(workflow.default.as_str()));
+
+        for destination in destinations {
+            if destination == "A" || destination == "R" {
+                continue;
+            }
+
+            if !workflows_by_name.contains_key(destination) {
+                return Err(CompileError::UndefinedWorkflow(UndefinedWorkflow {
+                    from: name.to_string(),
+                    name: destination.to_string(),
+                }));
+            }
+
+            Self::visit(destination, workflows_by_name, path, finished)?;
+        }
+
+        path.pop();
+        finished.insert(name);
+
+        Ok(())
+    }
+
+    // Whether the part is accepted, or None if it lies outside the compiled bounds
+    pub fn evaluate(&self, part: &Part) -> Option<bool> {
+        if !self.bounds.contains(part.get_ratings()) {
+            return None;
+        }
+
+        let mut curr = self.root;
+
+        loop {
+            match self.nodes[curr] {
+                Node::Accept => return Some(true),
+                Node::Reject => return Some(false),
+                Node::Branch {
+                    condition,
+                    if_true,
+                    if_false,
+                } => {
+                    curr = if condition.matches(part) {
+                        if_true
+                    } else {
+                        if_false
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn accepted_ranges(&self, bounds: HyperRect) -> Vec<HyperRect> {
+        let mut accepted_ranges = Vec::new();
+        let mut stack = vec![(self.root, bounds)];
+
+        while let Some((id, range)) = stack.pop() {
+            match self.nodes[id] {
+                Node::Accept => accepted_ranges.push(range),
+                Node::Reject => {}
+                Node::Branch {
+                    condition,
+                    if_true,
+                    if_false,
+                } => {
+                    let (matched_range, rest_range) = condition.split(&range);
+
+                    if let Some(matched_range) = matched_range {
+                        stack.push((if_true, matched_range));
+                    }
+
+                    if let Some(rest_range) = rest_range {
+                        stack.push((if_false, rest_range));
+                    }
+                }
+            }
+        }
+
+        accepted_ranges
+    }
+}
+
+// What to compile once a range reaches some point in a workflow: a named target, the
+// rules of a workflow from some index on, or a condition with both of its outcomes
+#[derive(Clone)]
+enum Next<'a> {
+    Target(&'a str),
+    Rules(&'a Workflow, usize),
+    Test(&'a Condition, Box<Next<'a>>, Box<Next<'a>>),
+    Compare(Comparison, Box<Next<'a>>, Box<Next<'a>>),
+}
+
+struct Compiler<'a> {
+    workflows_by_name: HashMap<&'a str, &'a Workflow>,
+    nodes: Vec<Node>,
+    interned: HashMap<Node, NodeId>,
+    // Workflows already compiled, keyed by name and by the range they were pruned against,
+    // so a workflow referenced from several places with the same range is compiled once
+    compiled: HashMap<(&'a str, HyperRect), NodeId>,
+}
+
+impl<'a> Compiler<'a> {
+    // `range` is everything that can still reach `next`, which lets comparisons that are
+    // always (or never) true for it be dropped outright along with the dead side
+    fn compile(&mut self, next: &Next<'a>, range: HyperRect) -> NodeId {
+        match next {
+            Next::Target("A") => ACCEPT,
+            Next::Target("R") => REJECT,
+            Next::Target(name) => {
+                let key = (*name, range);
+
+                if let Some(&id) = self.compiled.get(&key) {
+                    return id;
+                }
+
+                let workflow = self.workflows_by_name[name];
+                let id = self.compile(&Next::Rules(workflow, 0), key.1.clone());
+                self.compiled.insert(key, id);
+
+                id
+            }
+            Next::Rules(workflow, idx) => match workflow.instructions.get(*idx) {
+                Some(instruction) => self.compile(
+                    &Next::Test(
+                        &instruction.condition,
+                        Box::new(Next::Target(&instruction.destination)),
+                        Box::new(Next::Rules(workflow, idx + 1)),
+                    ),
+                    range,
+                ),
+                None => self.compile(&Next::Target(&workflow.default), range),
+            },
+            Next::Test(condition, if_true, if_false) => {
+                let expanded = match condition {
+                    Condition::Compare(comparison) => comparison
+                        .to_one_sided()
+                        .into_iter()
+                        .rev()
+                        .fold(*if_true.clone(), |acc, one_sided| {
+                            Next::Compare(one_sided, Box::new(acc), if_false.clone())
+                        }),
+                    Condition::And(left, right) => Next::Test(
+                        left,
+                        Box::new(Next::Test(right, if_true.clone(), if_false.clone())),
+                        if_false.clone(),
+                    ),
+                    Condition::Or(left, right) => Next::Test(
+                        left,
+                        if_true.clone(),
+                        Box::new(Next::Test(right, if_true.clone(), if_false.clone())),
+                    ),
+                };
+
+                self.compile(&expanded, range)
+            }
+            Next::Compare(comparison, if_true, if_false) => match comparison.split(&range) {
+                (Some(matched_range), Some(rest_range)) => {
+                    let if_true = self.compile(if_true, matched_range);
+                    let if_false = self.compile(if_false, rest_range);
+
+                    self.branch(*comparison, if_true, if_false)
+                }
+                (Some(matched_range), None) => self.compile(if_true, matched_range),
+                (None, Some(rest_range)) => self.compile(if_false, rest_range),
+                (None, None) => unreachable!(),
+            },
+        }
+    }
+
+    fn branch(&mut self, condition: Comparison, if_true: NodeId, if_false: NodeId) -> NodeId {
+        if if_true == if_false {
+            return if_true;
+        }
+
+        let node = Node::Branch {
+            condition,
+            if_true,
+            if_false,
+        };
+
+        if let Some(&id) = self.interned.get(&node) {
+            return id;
+        }
+
+        self.nodes.push(node);
+        self.interned.insert(node, self.nodes.len() - 1);
+
+        self.nodes.len() - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::decision::{CompileError, DecisionDag, Node};
+    use crate::hyper_rect::HyperRect;
+    use crate::parse_lines;
+    use crate::tests::example_lines;
+    use crate::trace::{PartTrace, Verdict};
+    use crate::workflow::{
+        Comparison, Condition, Instruction, Operator, Part, UndefinedWorkflow, Workflow,
+    };
+
+    fn workflow(name: &str, rules: &[(usize, Operator, u64, &str)], default: &str) -> Workflow {
+        Workflow::new(
+            name.to_string(),
+            rules
+                .iter()
+                .map(|&(axis, operator, value, destination)| {
+                    Instruction::new(
+                        destination.to_string(),
+                        Condition::Compare(Comparison::new(axis, operator, value)),
+                    )
+                })
+                .collect(),
+            default.to_string(),
+        )
+    }
+
+    fn bounds() -> HyperRect {
+        HyperRect::new([1..=100, 1..=100])
+    }
+
+    fn count_accepted(dag: &DecisionDag) -> u128 {
+        dag.accepted_ranges(bounds())
+            .iter()
+            .map(|range| range.volume())
+            .sum()
+    }
+
+    #[test]
+    fn evaluates_and_counts() {
+        let workflows = vec![
+            workflow("in", &[(0, Operator::LessThan, 51, "lo")], "hi"),
+            workflow("lo", &[(1, Operator::GreaterThan, 90, "A")], "R"),
+            workflow("hi", &[(1, Operator::LessThan, 11, "A")], "R"),
+        ];
+        let dag = DecisionDag::compile(&workflows, bounds()).unwrap();
+
+        assert_eq!(dag.evaluate(&Part::new([50, 91])), Some(true));
+        assert_eq!(dag.evaluate(&Part::new([50, 90])), Some(false));
+        assert_eq!(dag.evaluate(&Part::new([51, 10])), Some(true));
+        assert_eq!(dag.evaluate(&Part::new([51, 91])), Some(false));
+        assert_eq!(dag.evaluate(&Part::new([0, 91])), None);
+        assert_eq!(dag.evaluate(&Part::new([50, 101])), None);
+        assert_eq!(count_accepted(&dag), 50 * 10 + 50 * 10);
+    }
+
+    #[test]
+    fn drops_rules_that_can_never_fire() {
+        // Everything reaching `a` has x < 10, so `x>20` is dead and so is the `R` default
+        let workflows = vec![
+            workflow("in", &[(0, Operator::LessThan, 10, "a")], "R"),
+            workflow(
+                "a",
+                &[
+                    (0, Operator::GreaterThan, 20, "R"),
+                    (0, Operator::LessThan, 50, "A"),
+                ],
+                "R",
+            ),
+        ];
+        let dag = DecisionDag::compile(&workflows, bounds()).unwrap();
+
+        assert_eq!(dag.nodes.len(), 3);
+        assert_eq!(
+            &dag.nodes[dag.root],
+            &Node::Branch {
+                condition: Comparison::new(0, Operator::LessThan, 10),
+                if_true: 0,
+                if_false: 1
+            }
+        );
+    }
+
+    #[test]
+    fn merges_equivalent_leaves() {
+        let workflows = vec![
+            workflow("in", &[(0, Operator::LessThan, 10, "a")], "b"),
+            workflow("a", &[(1, Operator::LessThan, 10, "A")], "A"),
+            workflow("b", &[(1, Operator::GreaterThan, 10, "c")], "A"),
+            workflow("c", &[], "A"),
+        ];
+        let dag = DecisionDag::compile(&workflows, bounds()).unwrap();
+
+        assert_eq!(&dag.nodes[dag.root], &Node::Accept);
+        assert_eq!(count_accepted(&dag), 100 * 100);
+    }
+
+    #[test]
+    fn shares_identical_subgraphs() {
+        let workflows = vec![
+            workflow("in", &[(0, Operator::LessThan, 10, "a")], "b"),
+            workflow("a", &[(1, Operator::LessThan, 10, "A")], "R"),
+            workflow("b", &[(1, Operator::LessThan, 10, "A")], "R"),
+        ];
+        let dag = DecisionDag::compile(&workflows, bounds()).unwrap();
+
+        // `a` and `b` compile to the same node, which makes the root test redundant too
+        assert_eq!(dag.nodes.len(), 3);
+    }
+
+    #[test]
+    fn rejects_undefined_and_cyclic_workflows() {
+        let undefined = vec![workflow("in", &[(0, Operator::LessThan, 10, "zz")], "R")];
+
+        assert_eq!(
+            DecisionDag::compile(&undefined, bounds()).unwrap_err(),
+            CompileError::UndefinedWorkflow(UndefinedWorkflow {
+                from: String::from("in"),
+                name: String::from("zz")
+            })
+        );
+
+        let cyclic = vec![
+            workflow("in", &[(0, Operator::LessThan, 10, "a")], "R"),
+            workflow("a", &[(1, Operator::LessThan, 10, "b")], "A"),
+            workflow("b", &[], "a"),
+        ];
+
+        assert_eq!(
+            DecisionDag::compile(&cyclic, bounds()).unwrap_err(),
+            CompileError::Cycle(vec![
+                String::from("a"),
+                String::from("b"),
+                String::from("a")
+            ])
+        );
+
+        assert_eq!(
+            DecisionDag::compile(&[workflow("qq", &[], "A")], bounds()).unwrap_err(),
+            CompileError::UndefinedWorkflow(UndefinedWorkflow {
+                from: String::new(),
+                name: String::from("in")
+            })
+        );
+    }
+
+    #[test]
+    fn agrees_with_tracing_on_example() {
+        let (axes, workflows, parts) = parse_lines(example_lines());
+        let dag = DecisionDag::compile(&workflows, axes.full_rect()).unwrap();
+
+        for part in parts.iter() {
+            let trace = PartTrace::trace(&workflows, &axes, part).unwrap();
+
+            assert_eq!(dag.evaluate(part), Some(trace.verdict == Verdict::Accepted));
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use std::ops::RangeInclusive;
+
+// An axis-aligned box of integer points, inclusive on both ends of every axis. A box is
+// empty as soon as any axis has `start > end`.
+#[derive(Hash, Clone, Eq, PartialEq, Debug)]
+pub(crate) struct HyperRect {
+    bounds: Vec<(u64, u64)>,
+}
+
+impl HyperRect {
+    pub fn new(bounds: impl IntoIterator<Item = RangeInclusive<u64>>) -> Self {
+        HyperRect {
+            bounds: bounds
+                .into_iter()
+                .map(|range| (*range.start(), *range.end()))
+                .collect(),
+        }
+    }
+
+    pub fn get_bounds(&self, axis: usize) -> RangeInclusive<u64> {
+        let (start, end) = self.bounds[axis];
+        start..=end
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bounds.iter().any(|&(start, end)| start > end)
+    }
+
+    // Widened so a full u64 axis, or a handful of puzzle-sized ones, can't overflow
+    pub fn volume(&self) -> u128 {
+        if self.is_empty() {
+            return 0;
+        }
+
+        self.bounds
+            .iter()
+            .map(|&(start, end)| (end - start) as u128 + 1)
+            .product()
+    }
+
+    pub fn contains(&self, point: &[u64]) -> bool {
+        self.bounds
+            .iter()
+            .zip(point.iter())
+            .all(|(&(start, end), value)| (start..=end).contains(value))
+    }
+
+    // Cuts the box in two along `axis`: everything strictly below `pivot`, and everything
+    // from `pivot` upwards. Either half is dropped if nothing is left in it.
+    pub fn split(&self, axis: usize, pivot: u64) -> (Option<HyperRect>, Option<HyperRect>) {
+        let (start, end) = self.bounds[axis];
+
+        let below = pivot.checked_sub(1).and_then(|below_end| {
+            let mut bounds = self.bounds.clone();
+            bounds[axis] = (start, end.min(below_end));
+            HyperRect { bounds }.non_empty()
+        });
+
+        let mut bounds = self.bounds.clone();
+        bounds[axis] = (start.max(pivot), end);
+        let above = HyperRect { bounds }.non_empty();
+
+        (below, above)
+    }
+
+    // Like `split`, but the cut falls just after `value`: everything up to and including
+    // it, and everything above it
+    pub fn split_after(&self, axis: usize, value: u64) -> (Option<HyperRect>, Option<HyperRect>) {
+        match value.checked_add(1) {
+            Some(pivot) => self.split(axis, pivot),
+            None => (self.clone().non_empty(), None),
+        }
+    }
+
+    fn non_empty(self) -> Option<HyperRect> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self)
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Axes {
+    names: Vec<String>,
+    bounds: HyperRect,
+}
+
+impl Axes {
+    pub fn new(names: impl Into<Vec<String>>, bounds: HyperRect) -> Self {
+        Axes {
+            names: names.into(),
+            bounds,
+        }
+    }
+
+    pub fn num_axes(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    pub fn get_name(&self, axis: usize) -> &str {
+        &self.names[axis]
+    }
+
+    pub fn full_rect(&self) -> HyperRect {
+        self.bounds.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hyper_rect::{Axes, HyperRect};
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn volume_and_emptiness() {
+        assert_eq!(
+            HyperRect::new([1..=4000, 1..=4000, 1..=4000, 1..=4000]).volume(),
+            4000_u128.pow(4)
+        );
+        assert_eq!(
+            HyperRect::new([1..=1415, 1..=4000, 1..=2005, 1..=1350]).volume(),
+            1415 * 4000 * 2005 * 1350
+        );
+        assert!(HyperRect::new([1..=10, 5..=4]).is_empty());
+        assert_eq!(HyperRect::new([1..=10, 5..=4]).volume(), 0);
+    }
+
+    #[test]
+    fn split_drops_empty_halves() {
+        let rect = HyperRect::new([1..=100, 1..=100]);
+
+        assert_eq!(
+            rect.split(0, 50),
+            (
+                Some(HyperRect::new([1..=49, 1..=100])),
+                Some(HyperRect::new([50..=100, 1..=100]))
+            )
+        );
+        assert_eq!(rect.split(1, 1), (None, Some(rect.clone())));
+        assert_eq!(rect.split(1, 0), (None, Some(rect.clone())));
+        assert_eq!(rect.split(1, 101), (Some(rect.clone()), None));
+        assert_eq!(rect.split(1, 4001), (Some(rect.clone()), None));
+    }
+
+    #[test]
+    fn contains_points() {
+        let a = HyperRect::new([1..=10, 1..=10, 1..=10]);
+
+        assert!(a.contains(&[1, 10, 5]));
+        assert!(!a.contains(&[0, 10, 5]));
+    }
+
+    #[test]
+    fn axes_lookup() {
+        let axes = Axes::new(
+            [String::from("x"), String::from("m")],
+            HyperRect::new([1..=4000, 1..=4000]),
+        );
+
+        assert_eq!(axes.index_of("m"), Some(1));
+        assert_eq!(axes.index_of("s"), None);
+        assert_eq!(axes.get_name(0), "x");
+        assert_eq!(axes.full_rect().volume(), 16_000_000);
+    }
+}
@@ -1,59 +1,184 @@
+mod decision;
+mod hyper_rect;
+mod rule_parser;
+mod trace;
 mod workflow;
 
-use crate::workflow::{Instruction, Operator, Part, PartRange, Workflow};
+use crate::decision::DecisionDag;
+use crate::hyper_rect::{Axes, HyperRect};
+use crate::rule_parser::parse_condition;
+use crate::trace::PartTrace;
+use crate::workflow::{Instruction, Part, Workflow};
 use common::filereader;
 use regex::Regex;
-use std::collections::{HashMap, VecDeque};
+use std::collections::HashMap;
+use std::env;
+use std::ops::RangeInclusive;
 
-fn parse_instructions(line: &str) -> (Vec<Instruction>, String) {
-    let instruction_regex = Regex::new(r"(a|m|s|x)(>|<)(\d+):([A-z]+)").unwrap();
+const DEFAULT_BOUNDS: RangeInclusive<u64> = 1..=4000;
+
+enum ExplainFormat {
+    Pretty,
+    Json,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum InputError {
+    NoCategories,
+    BadDeclaration(String),
+    BadRating(String),
+    UnknownCategory(String),
+    MissingRating(String),
+    // Workflows are compiled against the declared bounds, so anything outside them would
+    // get a verdict for a range that was never considered
+    RatingOutOfBounds { category: String, value: u64 },
+}
+
+// Rating categories come from an optional leading `ratings: x=1..4000, m=1..4000, ...`
+// line. Without one, the names are taken from the first part and every category gets
+// the puzzle's default bounds.
+fn declared_categories(lines: &[String]) -> Result<Vec<(String, RangeInclusive<u64>)>, InputError> {
+    let declaration_regex = Regex::new(r"^ratings:\s*(.*)$").unwrap();
+    let bounds_regex = Regex::new(r"^(\w+)=(\d+)\.\.(\d+)$").unwrap();
+    let part_regex = Regex::new(r"^\{(.*)}$").unwrap();
+
+    if let Some(captures) = lines.first().and_then(|l| declaration_regex.captures(l)) {
+        captures
+            .get(1)
+            .unwrap()
+            .as_str()
+            .split(',')
+            .map(|entry| {
+                let captures = bounds_regex
+                    .captures(entry.trim())
+                    .ok_or_else(|| InputError::BadDeclaration(entry.to_string()))?;
+                let bound = |idx: usize| {
+                    captures
+                        .get(idx)
+                        .unwrap()
+                        .as_str()
+                        .parse::<u64>()
+                        .map_err(|_| InputError::BadDeclaration(entry.to_string()))
+                };
+
+                Ok((
+                    captures.get(1).unwrap().as_str().to_string(),
+                    bound(2)?..=bound(3)?,
+                ))
+            })
+            .collect()
+    } else if let Some(captures) = lines.iter().find_map(|l| part_regex.captures(l)) {
+        Ok(captures
+            .get(1)
+            .unwrap()
+            .as_str()
+            .split(',')
+            .map(|entry| {
+                let name = entry.split('=').next().unwrap();
+                (name.to_string(), DEFAULT_BOUNDS)
+            })
+            .collect())
+    } else {
+        Err(InputError::NoCategories)
+    }
+}
+
+fn parse_axes(lines: &[String]) -> Result<Axes, InputError> {
+    let (names, bounds): (Vec<String>, Vec<RangeInclusive<u64>>) =
+        declared_categories(lines)?.into_iter().unzip();
+
+    Ok(Axes::new(names, HyperRect::new(bounds)))
+}
+
+fn parse_instructions(line: &str, axes: &Axes) -> (Vec<Instruction>, String) {
+    let instruction_regex = Regex::new(r"^(.+):([A-Za-z]+)$").unwrap();
     let instruction_strings = line.split(",");
     let default = instruction_strings.clone().last().unwrap();
 
     let instructions: Vec<Instruction> = instruction_strings
         .clone()
-        .into_iter()
         .take(instruction_strings.count() - 1)
         .map(|segment| {
             if let Some(captures) = instruction_regex.captures(segment) {
-                let part_attribute = captures.get(1).unwrap().as_str().chars().next().unwrap();
-                let operator = match captures.get(2).unwrap().as_str().chars().next().unwrap() {
-                    '<' => Operator::LessThan,
-                    '>' => Operator::GreaterThan,
-                    _ => unreachable!(),
-                };
-                let value = captures.get(3).unwrap().as_str().parse::<usize>().unwrap();
-                let destination = captures.get(4).unwrap().as_str();
+                let rule = captures.get(1).unwrap().as_str();
+                let destination = captures.get(2).unwrap().as_str();
 
-                return Instruction::new(destination.to_string(), operator, value, part_attribute);
+                return match parse_condition(rule, axes) {
+                    Ok(condition) => Instruction::new(destination.to_string(), condition),
+                    Err(e) => panic!("Bad rule {}: {:?}", segment, e),
+                };
             }
 
-            unreachable!();
+            panic!("Bad instruction: {}", segment);
         })
         .collect();
 
     (instructions, default.to_string())
 }
 
-fn parse_lines(lines: Vec<String>) -> (Vec<Workflow>, Vec<Part>) {
+fn parse_part(line: &str, axes: &Axes) -> Result<Part, InputError> {
+    let mut ratings = vec![None; axes.num_axes()];
+
+    for entry in line
+        .trim_start_matches('{')
+        .trim_end_matches('}')
+        .split(',')
+    {
+        let (name, value) = entry
+            .split_once('=')
+            .ok_or_else(|| InputError::BadRating(entry.to_string()))?;
+        let axis = axes
+            .index_of(name)
+            .ok_or_else(|| InputError::UnknownCategory(name.to_string()))?;
+        let value = value
+            .parse::<u64>()
+            .map_err(|_| InputError::BadRating(entry.to_string()))?;
+
+        if !axes.full_rect().get_bounds(axis).contains(&value) {
+            return Err(InputError::RatingOutOfBounds {
+                category: name.to_string(),
+                value,
+            });
+        }
+
+        ratings[axis] = Some(value);
+    }
+
+    match ratings.into_iter().collect::<Option<Vec<u64>>>() {
+        Some(ratings) => Ok(Part::new(ratings)),
+        None => Err(InputError::MissingRating(line.to_string())),
+    }
+}
+
+fn parse_lines(lines: Vec<String>) -> (Axes, Vec<Workflow>, Vec<Part>) {
     let workflow_regex = Regex::new(r"([a-z]+)\{(.*)}").unwrap();
-    let part_regex = Regex::new(r"\{x=(\d+),m=(\d+),a=(\d+),s=(\d+)}").unwrap();
+    let axes = match parse_axes(&lines) {
+        Ok(axes) => axes,
+        Err(e) => panic!("Bad rating categories: {:?}", e),
+    };
     let mut line_idx = 0;
 
     let mut workflows = Vec::new();
     let mut parts = Vec::new();
 
+    if lines
+        .first()
+        .is_some_and(|line| line.starts_with("ratings:"))
+    {
+        line_idx += 1;
+    }
+
     while line_idx < lines.len() {
         let curr_line = lines[line_idx].as_str();
 
-        if curr_line.len() == 0 {
+        if curr_line.is_empty() {
             line_idx += 1;
             break;
         }
 
         let captures = workflow_regex.captures(curr_line).unwrap();
         let workflow_name = captures.get(1).unwrap().as_str().to_owned();
-        let (instructions, default) = parse_instructions(captures.get(2).unwrap().as_str());
+        let (instructions, default) = parse_instructions(captures.get(2).unwrap().as_str(), &axes);
 
         workflows.push(Workflow::new(workflow_name, instructions, default));
 
@@ -61,180 +186,104 @@ fn parse_lines(lines: Vec<String>) -> (Vec<Workflow>, Vec<Part>) {
     }
 
     while line_idx < lines.len() {
-        let captures = part_regex.captures(lines[line_idx].as_str()).unwrap();
-
-        parts.push(Part::new(
-            captures.get(1).unwrap().as_str().parse::<usize>().unwrap(),
-            captures.get(2).unwrap().as_str().parse::<usize>().unwrap(),
-            captures.get(3).unwrap().as_str().parse::<usize>().unwrap(),
-            captures.get(4).unwrap().as_str().parse::<usize>().unwrap(),
-        ));
+        match parse_part(lines[line_idx].as_str(), &axes) {
+            Ok(part) => parts.push(part),
+            Err(e) => panic!("Bad part {}: {:?}", lines[line_idx], e),
+        }
 
         line_idx += 1;
     }
 
-    (workflows, parts)
+    (axes, workflows, parts)
 }
 
-fn run_workflows(workflows: Vec<Workflow>, parts: Vec<Part>) -> HashMap<Part, bool> {
-    let workflows_by_name = workflows.into_iter().fold(HashMap::new(), |mut hmap, wf| {
-        hmap.insert(wf.name.clone(), wf);
-        hmap
-    });
-
-    let mut parts_to_paths: HashMap<Part, Vec<&str>> = HashMap::new();
-    let mut parts_to_acceptance = HashMap::new();
-
-    'part: for part in parts {
-        let mut curr_flow = workflows_by_name.get(&String::from("in")).unwrap();
-        let mut curr_path = vec!["in"];
-
-        'flows: loop {
-            for instruction in &curr_flow.instructions {
-                match instruction.matches(part) {
-                    true => {
-                        if instruction.destination == "A" {
-                            parts_to_acceptance.insert(part, true);
-                            curr_path.push(&instruction.destination);
-                            parts_to_paths.insert(part, curr_path);
-                            continue 'part;
-                        } else if instruction.destination == "R" {
-                            parts_to_acceptance.insert(part, false);
-                            curr_path.push(&instruction.destination);
-                            parts_to_paths.insert(part, curr_path);
-                            continue 'part;
-                        }
-                        curr_flow = workflows_by_name.get(&instruction.destination).unwrap();
-                        curr_path.push(&instruction.destination);
-                        continue 'flows;
-                    }
-                    false => {}
-                }
-            }
-
-            if curr_flow.default == "A" {
-                parts_to_acceptance.insert(part, true);
-                curr_path.push("A");
-                parts_to_paths.insert(part, curr_path);
-                continue 'part;
-            } else if curr_flow.default == "R" {
-                parts_to_acceptance.insert(part, false);
-                curr_path.push("R");
-                parts_to_paths.insert(part, curr_path);
-                continue 'part;
-            }
-
-            curr_path.push(&curr_flow.default);
-            curr_flow = workflows_by_name.get(&curr_flow.default).unwrap();
-        }
+fn compile_workflows(workflows: Vec<Workflow>, bounds: HyperRect) -> DecisionDag {
+    match DecisionDag::compile(&workflows, bounds) {
+        Ok(dag) => dag,
+        Err(e) => panic!("Failed to compile workflows: {:?}", e),
     }
+}
 
-    // println!("Paths: {:?}", parts_to_paths);
+fn run_workflows(
+    workflows: Vec<Workflow>,
+    parts: Vec<Part>,
+    bounds: HyperRect,
+) -> HashMap<Part, bool> {
+    let dag = compile_workflows(workflows, bounds);
 
-    parts_to_acceptance
+    parts
+        .into_iter()
+        .map(|part| match dag.evaluate(&part) {
+            Some(accepted) => (part, accepted),
+            None => panic!("Part {:?} lies outside the rating bounds", part),
+        })
+        .collect()
 }
 
-fn compute_acceptable_ranges(workflows: Vec<Workflow>) -> Vec<PartRange> {
-    let workflows_by_name = workflows.into_iter().fold(HashMap::new(), |mut hmap, wf| {
-        hmap.insert(wf.name.clone(), wf);
-        hmap
-    });
-
-    let mut accepted_ranges = Vec::new();
-    let mut stack = VecDeque::new();
-
-    stack.push_front((
-        workflows_by_name.get(&String::from("in")).unwrap(),
-        PartRange::default(),
-    ));
-
-    while let Some((wf, range)) = stack.pop_front() {
-        let mut curr_range = range.clone();
-
-        for instruction in &wf.instructions {
-            if instruction.operator == Operator::LessThan {
-                let (bottom_range, top_range) = curr_range.split_at(
-                    instruction.part_attribute,
-                    instruction.value,
-                    Operator::LessThan,
-                );
-
-                // Bottom range is destination
-                curr_range = top_range;
-
-                if instruction.destination == "A" {
-                    accepted_ranges.push(bottom_range);
-                } else if instruction.destination == "R" {
-                    continue;
-                } else {
-                    stack.push_front((
-                        workflows_by_name.get(&instruction.destination).unwrap(),
-                        bottom_range,
-                    ));
-                }
-            } else {
-                let (bottom_range, top_range) = curr_range.split_at(
-                    instruction.part_attribute,
-                    instruction.value,
-                    Operator::GreaterThan,
-                );
-
-                // Top range is destination
-                curr_range = bottom_range;
-
-                if instruction.destination == "A" {
-                    accepted_ranges.push(top_range);
-                } else if instruction.destination == "R" {
-                    continue;
-                } else {
-                    stack.push_front((
-                        workflows_by_name.get(&instruction.destination).unwrap(),
-                        top_range,
-                    ));
-                }
-            }
-        }
+fn compute_acceptable_ranges(workflows: Vec<Workflow>, bounds: HyperRect) -> Vec<HyperRect> {
+    compile_workflows(workflows, bounds.clone()).accepted_ranges(bounds)
+}
 
-        if wf.default == "A" {
-            accepted_ranges.push(curr_range);
-        } else if wf.default != "R" {
-            stack.push_front((workflows_by_name.get(&wf.default).unwrap(), curr_range));
-        }
-    }
+fn explain(lines: Vec<String>) -> Vec<PartTrace> {
+    let (axes, workflows, parts) = parse_lines(lines);
 
-    for range in accepted_ranges.iter() {
-        // println!("{:?}", range);
-    }
-    accepted_ranges
+    parts
+        .iter()
+        .map(|part| match PartTrace::trace(&workflows, &axes, part) {
+            Ok(trace) => trace,
+            Err(e) => panic!("Failed to trace part {:?}: {:?}", part, e),
+        })
+        .collect()
 }
 
 fn solve(lines: Vec<String>) -> u64 {
-    let (workflows, parts) = parse_lines(lines);
-    let results = run_workflows(workflows, parts);
-
-    results.iter().fold(0, |acc, (part, accepted)| {
-        if *accepted {
-            acc + part.sum() as u64
-        } else {
-            acc
-        }
-    })
+    let (axes, workflows, parts) = parse_lines(lines);
+    let results = run_workflows(workflows, parts, axes.full_rect());
+
+    results.iter().fold(
+        0,
+        |acc, (part, accepted)| {
+            if *accepted {
+                acc + part.sum()
+            } else {
+                acc
+            }
+        },
+    )
 }
 
-fn solve2(lines: Vec<String>) -> u64 {
-    let (workflows, _) = parse_lines(lines);
+fn solve2(lines: Vec<String>) -> u128 {
+    let (axes, workflows, _) = parse_lines(lines);
 
-    let accepted_ranges = compute_acceptable_ranges(workflows);
+    let accepted_ranges = compute_acceptable_ranges(workflows, axes.full_rect());
 
-    accepted_ranges
-        .iter()
-        .map(|range| range.compute_combos())
-        .sum()
+    accepted_ranges.iter().map(|range| range.volume()).sum()
+}
+
+// `--explain` prints how each part was routed; `--explain json` prints one JSON object per line
+fn explain_format() -> Option<ExplainFormat> {
+    let args: Vec<String> = env::args().collect();
+    let flag = args.iter().position(|arg| arg == "--explain")?;
+
+    match args.get(flag + 1).map(|arg| arg.as_str()) {
+        Some("json") => Some(ExplainFormat::Json),
+        Some("pretty") | None => Some(ExplainFormat::Pretty),
+        Some(arg) if arg.starts_with("--") => Some(ExplainFormat::Pretty),
+        Some(arg) => panic!("Unknown explain format {}", arg),
+    }
 }
 
 fn main() {
     match filereader::read_file("./day19/resources/input.txt") {
         Ok(lines) => {
+            if let Some(format) = explain_format() {
+                for trace in explain(lines.clone()) {
+                    match format {
+                        ExplainFormat::Pretty => println!("{}", trace.pretty()),
+                        ExplainFormat::Json => println!("{}", trace.to_json()),
+                    }
+                }
+            }
             let result = solve2(lines);
             println!("{:?}", result);
         }
@@ -244,7 +293,32 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
-    use crate::solve2;
+    use crate::{explain, parse_axes, parse_part, solve, solve2, InputError};
+
+    pub(crate) fn example_lines() -> Vec<String> {
+        [
+            "px{a<2006:qkq,m>2090:A,rfg}",
+            "pv{a>1716:R,A}",
+            "lnx{m>1548:A,A}",
+            "rfg{s<537:gd,x>2440:R,A}",
+            "qs{s>3448:A,lnx}",
+            "qkq{x<1416:A,crn}",
+            "crn{x>2662:A,R}",
+            "in{s<1351:px,qqz}",
+            "qqz{s>2770:qs,m<1801:hdj,R}",
+            "gd{a>3333:R,R}",
+            "hdj{m>838:A,pv}",
+            "",
+            "{x=787,m=2655,a=1222,s=2876}",
+            "{x=1679,m=44,a=2067,s=496}",
+            "{x=2036,m=264,a=79,s=2244}",
+            "{x=2461,m=1339,a=466,s=291}",
+            "{x=2127,m=1623,a=2188,s=1013}",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect()
+    }
 
     #[test]
     fn compute_acceptable_ranges_small() {
@@ -283,4 +357,133 @@ mod tests {
 
         assert_eq!(solve2(lines), 167409079868000)
     }
+
+    #[test]
+    fn explain_test_example() {
+        let lines = example_lines();
+
+        let summaries: Vec<String> = explain(lines)
+            .iter()
+            .map(|trace| trace.pretty().lines().next().unwrap().to_string())
+            .collect();
+
+        assert_eq!(
+            summaries,
+            vec![
+                "{x=787,m=2655,a=1222,s=2876}: in -> qqz -> qs -> lnx -> A (accepted)",
+                "{x=1679,m=44,a=2067,s=496}: in -> px -> rfg -> gd -> R (rejected)",
+                "{x=2036,m=264,a=79,s=2244}: in -> qqz -> hdj -> pv -> A (accepted)",
+                "{x=2461,m=1339,a=466,s=291}: in -> px -> qkq -> crn -> R (rejected)",
+                "{x=2127,m=1623,a=2188,s=1013}: in -> px -> rfg -> A (accepted)",
+            ]
+        );
+    }
+
+    #[test]
+    fn declared_rating_categories() {
+        let lines = vec![
+            String::from("ratings: w=1..10, h=1..10, d=0..9, q=5..5"),
+            String::from("in{w<4:A,d>6:R,hh}"),
+            String::from("hh{h>8:A,R}"),
+            String::from(""),
+            String::from("{w=3,h=1,d=9,q=5}"),
+        ];
+
+        // w in 1..=3 is accepted outright; of the rest, d in 0..=6 with h in 9..=10
+        assert_eq!(solve2(lines), 3 * 10 * 10 + 7 * 2 * 7);
+    }
+
+    #[test]
+    fn compound_rules() {
+        let lines = vec![
+            String::from("ratings: x=1..100, y=1..100, z=1..1, w=1..1"),
+            String::from("in{x==5:A,x<=10&&y>=50:A,x>90||y<3:R,mid}"),
+            String::from("mid{(y==7):A,R}"),
+            String::from(""),
+            String::from("{x=5,y=100,z=1,w=1}"),
+            String::from("{x=95,y=7,z=1,w=1}"),
+            String::from("{x=20,y=7,z=1,w=1}"),
+        ];
+
+        assert_eq!(solve(lines.clone()), 107 + 29);
+        assert_eq!(solve2(lines), 100 + 9 * 51 + 89);
+    }
+
+    #[test]
+    fn any_number_of_categories() {
+        let lines = vec![
+            String::from("ratings: a=1..10, b=1..3"),
+            String::from("in{a>7:A,R}"),
+            String::from(""),
+            String::from("{a=8,b=2}"),
+            String::from("{a=2,b=3}"),
+        ];
+
+        assert_eq!(solve(lines.clone()), 10);
+        assert_eq!(solve2(lines), 3 * 3);
+
+        let six = vec![
+            String::from("in{f<2:A,R}"),
+            String::from(""),
+            String::from("{a=1,b=1,c=1,d=1,e=1,f=1}"),
+        ];
+
+        assert_eq!(solve(six.clone()), 6);
+        assert_eq!(solve2(six), 4000_u128.pow(5));
+    }
+
+    #[test]
+    fn bad_category_declarations() {
+        assert_eq!(
+            parse_axes(&[String::from("ratings: a=1..x")]).unwrap_err(),
+            InputError::BadDeclaration(String::from("a=1..x"))
+        );
+        assert_eq!(
+            parse_axes(&[String::from("in{A}")]).unwrap_err(),
+            InputError::NoCategories
+        );
+    }
+
+    #[test]
+    fn nine_categories() {
+        let lines = vec![
+            String::from("in{i>1:R,A}"),
+            String::from(""),
+            String::from("{a=1,b=1,c=1,d=1,e=1,f=1,g=1,h=1,i=1}"),
+        ];
+
+        assert_eq!(solve(lines.clone()), 9);
+        assert_eq!(solve2(lines), 4000_u128.pow(8));
+    }
+
+    #[test]
+    fn parts_must_lie_within_declared_bounds() {
+        let lines = vec![String::from("ratings: w=1..10, h=0..5")];
+        let axes = parse_axes(&lines).unwrap();
+
+        assert_eq!(
+            parse_part("{w=10,h=0}", &axes).unwrap().get_ratings(),
+            &[10, 0]
+        );
+        assert!(matches!(
+            parse_part("{w=11,h=0}", &axes),
+            Err(InputError::RatingOutOfBounds { value: 11, .. })
+        ));
+        assert!(matches!(
+            parse_part("{w=0,h=0}", &axes),
+            Err(InputError::RatingOutOfBounds { value: 0, .. })
+        ));
+        assert!(matches!(
+            parse_part("{w=3}", &axes),
+            Err(InputError::MissingRating(_))
+        ));
+        assert!(matches!(
+            parse_part("{w=3,z=1}", &axes),
+            Err(InputError::UnknownCategory(_))
+        ));
+        assert!(matches!(
+            parse_part("{w=3,h=x}", &axes),
+            Err(InputError::BadRating(_))
+        ));
+    }
 }
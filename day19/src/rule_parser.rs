@@ -0,0 +1,276 @@
+use crate::hyper_rect::Axes;
+use crate::workflow::{Comparison, Condition, Operator};
+use std::num::ParseIntError;
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum RuleParseError {
+    UnexpectedToken(String),
+    UnexpectedEnd,
+    UnknownCategory(String),
+    ParseError(ParseIntError),
+}
+
+impl From<ParseIntError> for RuleParseError {
+    fn from(err: ParseIntError) -> Self {
+        RuleParseError::ParseError(err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Name(String),
+    Number(String),
+    Operator(Operator),
+    And,
+    Or,
+    Open,
+    Close,
+}
+
+fn tokenize(rule: &str) -> Result<Vec<Token>, RuleParseError> {
+    let chars: Vec<char> = rule.chars().collect();
+    let mut tokens = Vec::new();
+    let mut idx = 0;
+
+    while idx < chars.len() {
+        let c = chars[idx];
+        let next = chars.get(idx + 1).copied();
+
+        if c.is_whitespace() {
+            idx += 1;
+            continue;
+        }
+
+        if c.is_ascii_alphabetic() || c == '_' {
+            let start = idx;
+            while idx < chars.len() && (chars[idx].is_ascii_alphanumeric() || chars[idx] == '_') {
+                idx += 1;
+            }
+            tokens.push(Token::Name(chars[start..idx].iter().collect()));
+            continue;
+        }
+
+        if c.is_ascii_digit() {
+            let start = idx;
+            while idx < chars.len() && chars[idx].is_ascii_digit() {
+                idx += 1;
+            }
+            tokens.push(Token::Number(chars[start..idx].iter().collect()));
+            continue;
+        }
+
+        let (token, len) = match (c, next) {
+            ('<', Some('=')) => (Token::Operator(Operator::LessOrEqual), 2),
+            ('>', Some('=')) => (Token::Operator(Operator::GreaterOrEqual), 2),
+            ('=', Some('=')) => (Token::Operator(Operator::Equal), 2),
+            ('&', Some('&')) => (Token::And, 2),
+            ('|', Some('|')) => (Token::Or, 2),
+            ('<', _) => (Token::Operator(Operator::LessThan), 1),
+            ('>', _) => (Token::Operator(Operator::GreaterThan), 1),
+            ('(', _) => (Token::Open, 1),
+            (')', _) => (Token::Close, 1),
+            _ => return Err(RuleParseError::UnexpectedToken(c.to_string())),
+        };
+
+        tokens.push(token);
+        idx += len;
+    }
+
+    Ok(tokens)
+}
+
+// Recursive descent over
+//   condition  := conjunction ("||" conjunction)*
+//   conjunction := atom ("&&" atom)*
+//   atom       := name operator number | "(" condition ")"
+// so `&&` binds tighter than `||`, as it does in most languages
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    idx: usize,
+    axes: &'a Axes,
+}
+
+impl<'a> Parser<'a> {
+    fn next(&mut self) -> Result<Token, RuleParseError> {
+        let token = self
+            .tokens
+            .get(self.idx)
+            .cloned()
+            .ok_or(RuleParseError::UnexpectedEnd)?;
+        self.idx += 1;
+
+        Ok(token)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.idx)
+    }
+
+    fn parse_condition(&mut self) -> Result<Condition, RuleParseError> {
+        let mut condition = self.parse_conjunction()?;
+
+        while self.peek() == Some(&Token::Or) {
+            self.idx += 1;
+            condition = Condition::Or(Box::new(condition), Box::new(self.parse_conjunction()?));
+        }
+
+        Ok(condition)
+    }
+
+    fn parse_conjunction(&mut self) -> Result<Condition, RuleParseError> {
+        let mut condition = self.parse_atom()?;
+
+        while self.peek() == Some(&Token::And) {
+            self.idx += 1;
+            condition = Condition::And(Box::new(condition), Box::new(self.parse_atom()?));
+        }
+
+        Ok(condition)
+    }
+
+    fn parse_atom(&mut self) -> Result<Condition, RuleParseError> {
+        match self.next()? {
+            Token::Open => {
+                let condition = self.parse_condition()?;
+
+                match self.next()? {
+                    Token::Close => Ok(condition),
+                    token => Err(RuleParseError::UnexpectedToken(format!("{:?}", token))),
+                }
+            }
+            Token::Name(name) => {
+                let axis = self
+                    .axes
+                    .index_of(&name)
+                    .ok_or(RuleParseError::UnknownCategory(name))?;
+
+                let operator = match self.next()? {
+                    Token::Operator(operator) => operator,
+                    token => return Err(RuleParseError::UnexpectedToken(format!("{:?}", token))),
+                };
+
+                let value = match self.next()? {
+                    Token::Number(number) => number.parse::<u64>()?,
+                    token => return Err(RuleParseError::UnexpectedToken(format!("{:?}", token))),
+                };
+
+                Ok(Condition::Compare(Comparison::new(axis, operator, value)))
+            }
+            token => Err(RuleParseError::UnexpectedToken(format!("{:?}", token))),
+        }
+    }
+}
+
+pub(crate) fn parse_condition(rule: &str, axes: &Axes) -> Result<Condition, RuleParseError> {
+    let mut parser = Parser {
+        tokens: tokenize(rule)?,
+        idx: 0,
+        axes,
+    };
+
+    let condition = parser.parse_condition()?;
+
+    match parser.peek() {
+        None => Ok(condition),
+        Some(token) => Err(RuleParseError::UnexpectedToken(format!("{:?}", token))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hyper_rect::{Axes, HyperRect};
+    use crate::rule_parser::{parse_condition, RuleParseError};
+    use crate::workflow::Part;
+
+    fn axes() -> Axes {
+        Axes::new(
+            [
+                String::from("x"),
+                String::from("cost_2"),
+                String::from("weight"),
+            ],
+            HyperRect::new([1..=4000, 1..=4000, 1..=4000]),
+        )
+    }
+
+    #[test]
+    fn parses_simple_comparisons() {
+        let axes = axes();
+
+        for (rule, matching, failing) in [
+            ("x<10", 9, 10),
+            ("x<=10", 10, 11),
+            ("x>10", 11, 10),
+            ("x>=10", 10, 9),
+            ("x==10", 10, 11),
+        ] {
+            let condition = parse_condition(rule, &axes).unwrap();
+
+            assert!(condition.matches(&Part::new([matching, 0, 0])), "{}", rule);
+            assert!(!condition.matches(&Part::new([failing, 0, 0])), "{}", rule);
+        }
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let axes = axes();
+        let condition = parse_condition("x<10 || cost_2>5 && weight==3", &axes).unwrap();
+
+        assert!(condition.matches(&Part::new([1, 0, 0])));
+        assert!(condition.matches(&Part::new([20, 6, 3])));
+        assert!(!condition.matches(&Part::new([20, 6, 4])));
+
+        let grouped = parse_condition("(x<10 || cost_2>5) && weight==3", &axes).unwrap();
+
+        assert!(!grouped.matches(&Part::new([1, 0, 0])));
+        assert!(grouped.matches(&Part::new([1, 0, 3])));
+    }
+
+    #[test]
+    fn reports_bad_rules() {
+        let axes = axes();
+
+        assert_eq!(
+            parse_condition("s<10", &axes).unwrap_err(),
+            RuleParseError::UnknownCategory(String::from("s"))
+        );
+        assert_eq!(
+            parse_condition("x<10 &&", &axes).unwrap_err(),
+            RuleParseError::UnexpectedEnd
+        );
+        assert_eq!(
+            parse_condition("x=10", &axes).unwrap_err(),
+            RuleParseError::UnexpectedToken(String::from("="))
+        );
+        assert!(matches!(
+            parse_condition("(x<10", &axes).unwrap_err(),
+            RuleParseError::UnexpectedEnd
+        ));
+        assert!(matches!(
+            parse_condition("x<99999999999999999999", &axes).unwrap_err(),
+            RuleParseError::ParseError(_)
+        ));
+    }
+
+    #[test]
+    fn format_round_trips() {
+        let axes = axes();
+
+        for rule in [
+            "x<10",
+            "x<10||cost_2>5||weight==3",
+            "x<10||(cost_2>5||weight==3)",
+            "x<10&&(cost_2>5&&weight==3)",
+            "(x<10||cost_2>5)&&weight==3",
+            "x<10&&(cost_2>5||weight==3)",
+            "x<10||cost_2>5&&weight==3",
+            "(x<1||x>5)&&(weight<=2||cost_2>=3&&x==4)",
+        ] {
+            let condition = parse_condition(rule, &axes).unwrap();
+            let formatted = condition.format(&axes);
+
+            assert_eq!(formatted, rule);
+            assert_eq!(parse_condition(&formatted, &axes).unwrap(), condition);
+        }
+    }
+}
@@ -0,0 +1,333 @@
+use crate::hyper_rect::Axes;
+use crate::workflow::{workflows_by_name, Part, UndefinedWorkflow, Workflow, ENTRY_WORKFLOW};
+use common::json::json_string;
+
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) enum TraceError {
+    UndefinedWorkflow(UndefinedWorkflow),
+    // The part came back round to a workflow it had already been through
+    Loop(Vec<String>),
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum Verdict {
+    Accepted,
+    Rejected,
+}
+
+impl Verdict {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Verdict::Accepted => "accepted",
+            Verdict::Rejected => "rejected",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct RuleCheck {
+    pub index: usize,
+    pub rule: String,
+    pub destination: String,
+    pub matched: bool,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct WorkflowVisit {
+    pub workflow: String,
+    // Every rule that was evaluated, in order; only the last one can have matched
+    pub checks: Vec<RuleCheck>,
+    pub used_default: bool,
+    pub next: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct PartTrace {
+    pub ratings: Vec<(String, u64)>,
+    pub visits: Vec<WorkflowVisit>,
+    pub verdict: Verdict,
+}
+
+impl PartTrace {
+    pub fn trace(
+        workflows: &[Workflow],
+        axes: &Axes,
+        part: &Part,
+    ) -> Result<PartTrace, TraceError> {
+        let workflows_by_name = workflows_by_name(workflows);
+
+        let ratings = part
+            .get_ratings()
+            .iter()
+            .enumerate()
+            .map(|(axis, &rating)| (axes.get_name(axis).to_string(), rating))
+            .collect();
+
+        let mut visits: Vec<WorkflowVisit> = Vec::new();
+        let mut curr_name = ENTRY_WORKFLOW;
+        let mut from = String::new();
+
+        loop {
+            match curr_name {
+                "A" | "R" => {
+                    let verdict = if curr_name == "A" {
+                        Verdict::Accepted
+                    } else {
+                        Verdict::Rejected
+                    };
+
+                    return Ok(PartTrace {
+                        ratings,
+                        visits,
+                        verdict,
+                    });
+                }
+                _ => {}
+            }
+
+            if visits.iter().any(|visit| visit.workflow == curr_name) {
+                let mut path: Vec<String> = visits.iter().map(|v| v.workflow.clone()).collect();
+                path.push(curr_name.to_string());
+                return Err(TraceError::Loop(path));
+            }
+
+            let Some(&workflow) = workflows_by_name.get(curr_name) else {
+                return Err(TraceError::UndefinedWorkflow(UndefinedWorkflow {
+                    from,
+                    name: curr_name.to_string(),
+                }));
+            };
+
+            let mut checks = Vec::new();
+            let mut next = None;
+
+            for (index, instruction) in workflow.instructions.iter().enumerate() {
+                let matched = instruction.matches(part);
+
+                checks.push(RuleCheck {
+                    index,
+                    rule: instruction.condition.format(axes),
+                    destination: instruction.destination.clone(),
+                    matched,
+                });
+
+                if matched {
+                    next = Some(instruction.destination.as_str());
+                    break;
+                }
+            }
+
+            let used_default = next.is_none();
+            let next = next.unwrap_or(workflow.default.as_str());
+
+            visits.push(WorkflowVisit {
+                workflow: workflow.name.clone(),
+                checks,
+                used_default,
+                next: next.to_string(),
+            });
+
+            from = workflow.name.clone();
+            curr_name = next;
+        }
+    }
+
+    fn format_ratings(&self) -> String {
+        let ratings: Vec<String> = self
+            .ratings
+            .iter()
+            .map(|(name, rating)| format!("{}={}", name, rating))
+            .collect();
+
+        format!("{{{}}}", ratings.join(","))
+    }
+
+    // The puzzle's one-line summary followed by every rule that was checked, e.g.
+    //   {x=787,m=2655,a=1222,s=2876}: in -> qqz -> qs -> lnx -> A (accepted)
+    //     in: s<1351:px skipped, default -> qqz
+    pub fn pretty(&self) -> String {
+        let mut path: Vec<&str> = self.visits.iter().map(|v| v.workflow.as_str()).collect();
+        path.push(match self.verdict {
+            Verdict::Accepted => "A",
+            Verdict::Rejected => "R",
+        });
+
+        let mut lines = vec![format!(
+            "{}: {} ({})",
+            self.format_ratings(),
+            path.join(" -> "),
+            self.verdict.as_str()
+        )];
+
+        for visit in self.visits.iter() {
+            let mut steps: Vec<String> = visit
+                .checks
+                .iter()
+                .map(|check| {
+                    if check.matched {
+                        format!("{}:{} matched", check.rule, check.destination)
+                    } else {
+                        format!("{}:{} skipped", check.rule, check.destination)
+                    }
+                })
+                .collect();
+
+            if visit.used_default {
+                steps.push(format!("default -> {}", visit.next));
+            }
+
+            lines.push(format!("  {}: {}", visit.workflow, steps.join(", ")));
+        }
+
+        lines.join("\n")
+    }
+
+    pub fn to_json(&self) -> String {
+        let ratings: Vec<String> = self
+            .ratings
+            .iter()
+            .map(|(name, rating)| format!("{}:{}", json_string(name), rating))
+            .collect();
+
+        let visits: Vec<String> = self
+            .visits
+            .iter()
+            .map(|visit| {
+                let checks: Vec<String> = visit
+                    .checks
+                    .iter()
+                    .map(|check| {
+                        format!(
+                            "{{\"index\":{},\"rule\":{},\"destination\":{},\"matched\":{}}}",
+                            check.index,
+                            json_string(&check.rule),
+                            json_string(&check.destination),
+                            check.matched
+                        )
+                    })
+                    .collect();
+
+                format!(
+                    "{{\"workflow\":{},\"checks\":[{}],\"used_default\":{},\"next\":{}}}",
+                    json_string(&visit.workflow),
+                    checks.join(","),
+                    visit.used_default,
+                    json_string(&visit.next)
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\"part\":{{{}}},\"visits\":[{}],\"verdict\":{}}}",
+            ratings.join(","),
+            visits.join(","),
+            json_string(self.verdict.as_str())
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hyper_rect::{Axes, HyperRect};
+    use crate::rule_parser::parse_condition;
+    use crate::trace::{PartTrace, TraceError, Verdict};
+    use crate::workflow::{Instruction, Part, UndefinedWorkflow, Workflow};
+
+    fn axes() -> Axes {
+        Axes::new(
+            [String::from("x"), String::from("m")],
+            HyperRect::new([1..=4000, 1..=4000]),
+        )
+    }
+
+    fn workflow(name: &str, rules: &[(&str, &str)], default: &str) -> Workflow {
+        Workflow::new(
+            name.to_string(),
+            rules
+                .iter()
+                .map(|&(rule, destination)| {
+                    Instruction::new(
+                        destination.to_string(),
+                        parse_condition(rule, &axes()).unwrap(),
+                    )
+                })
+                .collect(),
+            default.to_string(),
+        )
+    }
+
+    #[test]
+    fn traces_every_rule_checked() {
+        let workflows = vec![
+            workflow("in", &[("x<100", "R"), ("m>10||x==500", "ab")], "R"),
+            workflow("ab", &[("x>=1000", "R")], "A"),
+        ];
+        let trace = PartTrace::trace(&workflows, &axes(), &Part::new([500, 3])).unwrap();
+
+        assert_eq!(trace.verdict, Verdict::Accepted);
+        assert_eq!(trace.visits.len(), 2);
+        assert_eq!(trace.visits[0].checks.len(), 2);
+        assert!(!trace.visits[0].checks[0].matched);
+        assert!(trace.visits[0].checks[1].matched);
+        assert!(!trace.visits[0].used_default);
+        assert!(trace.visits[1].used_default);
+
+        assert_eq!(
+            trace.pretty(),
+            [
+                "{x=500,m=3}: in -> ab -> A (accepted)",
+                "  in: x<100:R skipped, m>10||x==500:ab matched",
+                "  ab: x>=1000:R skipped, default -> A",
+            ]
+            .join("\n")
+        );
+
+        assert_eq!(
+            trace.to_json(),
+            concat!(
+                "{\"part\":{\"x\":500,\"m\":3},\"visits\":[",
+                "{\"workflow\":\"in\",\"checks\":[",
+                "{\"index\":0,\"rule\":\"x<100\",\"destination\":\"R\",\"matched\":false},",
+                "{\"index\":1,\"rule\":\"m>10||x==500\",\"destination\":\"ab\",\"matched\":true}",
+                "],\"used_default\":false,\"next\":\"ab\"},",
+                "{\"workflow\":\"ab\",\"checks\":[",
+                "{\"index\":0,\"rule\":\"x>=1000\",\"destination\":\"R\",\"matched\":false}",
+                "],\"used_default\":true,\"next\":\"A\"}",
+                "],\"verdict\":\"accepted\"}"
+            )
+        );
+    }
+
+    #[test]
+    fn reports_broken_routing() {
+        let undefined = vec![workflow("in", &[("x<100", "zz")], "R")];
+
+        assert_eq!(
+            PartTrace::trace(&undefined, &axes(), &Part::new([5, 5])).unwrap_err(),
+            TraceError::UndefinedWorkflow(UndefinedWorkflow {
+                from: String::from("in"),
+                name: String::from("zz")
+            })
+        );
+
+        let looping = vec![workflow("in", &[], "ab"), workflow("ab", &[], "in")];
+
+        assert_eq!(
+            PartTrace::trace(&looping, &axes(), &Part::new([5, 5])).unwrap_err(),
+            TraceError::Loop(vec![
+                String::from("in"),
+                String::from("ab"),
+                String::from("in")
+            ])
+        );
+    }
+
+    #[test]
+    fn bracketed_rules_round_trip() {
+        let workflows = vec![workflow("in", &[("(x<5||m<5)&&x>1", "A")], "R")];
+        let trace = PartTrace::trace(&workflows, &axes(), &Part::new([9, 9])).unwrap();
+
+        assert_eq!(trace.verdict, Verdict::Rejected);
+        assert_eq!(trace.visits[0].checks[0].rule, "(x<5||m<5)&&x>1");
+    }
+}
@@ -1,181 +1,182 @@
-use crate::workflow::Operator::LessThan;
-use std::ops::{Range, RangeBounds, RangeInclusive};
+use crate::hyper_rect::{Axes, HyperRect};
+use std::collections::HashMap;
 
-#[derive(Debug, Eq, PartialEq)]
+// Every part starts out in this workflow
+pub(crate) const ENTRY_WORKFLOW: &str = "in";
+
+// A reference to a workflow that isn't defined; `from` is empty for the entry workflow
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct UndefinedWorkflow {
+    pub from: String,
+    pub name: String,
+}
+
+#[derive(Hash, Clone, Copy, Eq, PartialEq, Debug)]
 pub(crate) enum Operator {
     LessThan,
+    LessOrEqual,
     GreaterThan,
+    GreaterOrEqual,
+    Equal,
 }
 
-#[derive(Hash, Clone, Copy, Eq, PartialEq, Debug)]
+impl Operator {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Operator::LessThan => "<",
+            Operator::LessOrEqual => "<=",
+            Operator::GreaterThan => ">",
+            Operator::GreaterOrEqual => ">=",
+            Operator::Equal => "==",
+        }
+    }
+}
+
+#[derive(Hash, Clone, Eq, PartialEq, Debug)]
 pub(crate) struct Part {
-    x: usize,
-    m: usize,
-    a: usize,
-    s: usize,
+    ratings: Vec<u64>,
 }
 
 impl Part {
-    pub fn new(x: usize, m: usize, a: usize, s: usize) -> Self {
-        Part { x, m, a, s }
+    pub fn new(ratings: impl Into<Vec<u64>>) -> Self {
+        Part {
+            ratings: ratings.into(),
+        }
+    }
+
+    pub fn get_ratings(&self) -> &[u64] {
+        &self.ratings
     }
 
-    pub fn sum(&self) -> usize {
-        self.x + self.m + self.a + self.s
+    pub fn sum(&self) -> u64 {
+        self.ratings.iter().sum()
     }
 }
 
-#[derive(Hash, Clone, Eq, PartialEq, Debug)]
-pub(crate) struct PartRange {
-    x: RangeInclusive<usize>,
-    m: RangeInclusive<usize>,
-    a: RangeInclusive<usize>,
-    s: RangeInclusive<usize>,
+#[derive(Hash, Clone, Copy, Eq, PartialEq, Debug)]
+pub(crate) struct Comparison {
+    pub axis: usize,
+    pub operator: Operator,
+    pub value: u64,
 }
 
-impl PartRange {
-    pub fn new(
-        x: RangeInclusive<usize>,
-        m: RangeInclusive<usize>,
-        a: RangeInclusive<usize>,
-        s: RangeInclusive<usize>,
-    ) -> Self {
-        PartRange { x, m, a, s }
-    }
-
-    pub fn default() -> Self {
-        PartRange {
-            x: 1..=4000,
-            m: 1..=4000,
-            a: 1..=4000,
-            s: 1..=4000,
+impl Comparison {
+    pub fn new(axis: usize, operator: Operator, value: u64) -> Self {
+        Comparison {
+            axis,
+            operator,
+            value,
         }
     }
 
-    pub fn split_at(
-        &self,
-        field: char,
-        value: usize,
-        operator: Operator,
-    ) -> (PartRange, PartRange) {
-        if !(1..=4000).contains(&value) {
-            panic!("Bad value chosen for range 1..=4000: {}", value);
+    pub fn matches(&self, part: &Part) -> bool {
+        let value_to_compare = part.ratings[self.axis];
+
+        match self.operator {
+            Operator::LessThan => value_to_compare < self.value,
+            Operator::LessOrEqual => value_to_compare <= self.value,
+            Operator::GreaterThan => value_to_compare > self.value,
+            Operator::GreaterOrEqual => value_to_compare >= self.value,
+            Operator::Equal => value_to_compare == self.value,
+        }
+    }
+
+    // Equality is the only comparison that doesn't cut a range in two, so it's checked
+    // as a pair of one-sided comparisons that both have to hold
+    pub fn to_one_sided(self) -> Vec<Comparison> {
+        match self.operator {
+            Operator::Equal => vec![
+                Comparison::new(self.axis, Operator::GreaterOrEqual, self.value),
+                Comparison::new(self.axis, Operator::LessOrEqual, self.value),
+            ],
+            _ => vec![self],
+        }
+    }
+
+    // Splits `range` into the part that satisfies this comparison and the part that
+    // doesn't
+    pub fn split(&self, range: &HyperRect) -> (Option<HyperRect>, Option<HyperRect>) {
+        match self.operator {
+            Operator::LessThan => range.split(self.axis, self.value),
+            Operator::LessOrEqual => range.split_after(self.axis, self.value),
+            Operator::GreaterThan => {
+                let (bottom_range, top_range) = range.split_after(self.axis, self.value);
+                (top_range, bottom_range)
+            }
+            Operator::GreaterOrEqual => {
+                let (bottom_range, top_range) = range.split(self.axis, self.value);
+                (top_range, bottom_range)
+            }
+            Operator::Equal => unreachable!("split equality through to_one_sided"),
         }
+    }
+}
 
-        let adjusted_value = if operator == LessThan {
-            value - 1
-        } else {
-            value
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) enum Condition {
+    Compare(Comparison),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+impl Condition {
+    pub fn matches(&self, part: &Part) -> bool {
+        match self {
+            Condition::Compare(comparison) => comparison.matches(part),
+            Condition::And(left, right) => left.matches(part) && right.matches(part),
+            Condition::Or(left, right) => left.matches(part) || right.matches(part),
+        }
+    }
+
+    // Renders the condition back in rule syntax so that it parses back to the same tree.
+    // Both operators parse left-associated with `&&` binding tighter, so brackets go
+    // around an `||` inside `&&`, and around a right operand using the same operator.
+    pub fn format(&self, axes: &Axes) -> String {
+        let bracketed = |condition: &Condition, needs_brackets: bool| {
+            if needs_brackets {
+                format!("({})", condition.format(axes))
+            } else {
+                condition.format(axes)
+            }
         };
 
-        match field {
-            'x' => (
-                PartRange::new(
-                    *self.x.start()..=adjusted_value,
-                    self.m.clone(),
-                    self.a.clone(),
-                    self.s.clone(),
-                ),
-                PartRange::new(
-                    (adjusted_value + 1)..=*self.x.end(),
-                    self.m.clone(),
-                    self.a.clone(),
-                    self.s.clone(),
-                ),
-            ),
-            'm' => (
-                PartRange::new(
-                    self.x.clone(),
-                    *self.m.start()..=adjusted_value,
-                    self.a.clone(),
-                    self.s.clone(),
-                ),
-                PartRange::new(
-                    self.x.clone(),
-                    (adjusted_value + 1)..=*self.m.end(),
-                    self.a.clone(),
-                    self.s.clone(),
-                ),
+        match self {
+            Condition::Compare(comparison) => format!(
+                "{}{}{}",
+                axes.get_name(comparison.axis),
+                comparison.operator.as_str(),
+                comparison.value
             ),
-            'a' => (
-                PartRange::new(
-                    self.x.clone(),
-                    self.m.clone(),
-                    *self.a.start()..=adjusted_value,
-                    self.s.clone(),
-                ),
-                PartRange::new(
-                    self.x.clone(),
-                    self.m.clone(),
-                    (adjusted_value + 1)..=*self.a.end(),
-                    self.s.clone(),
-                ),
+            Condition::And(left, right) => format!(
+                "{}&&{}",
+                bracketed(left, matches!(**left, Condition::Or(_, _))),
+                bracketed(right, !matches!(**right, Condition::Compare(_)))
             ),
-            's' => (
-                PartRange::new(
-                    self.x.clone(),
-                    self.m.clone(),
-                    self.a.clone(),
-                    *self.s.start()..=adjusted_value,
-                ),
-                PartRange::new(
-                    self.x.clone(),
-                    self.m.clone(),
-                    self.a.clone(),
-                    (adjusted_value + 1)..=*self.s.end(),
-                ),
+            Condition::Or(left, right) => format!(
+                "{}||{}",
+                left.format(axes),
+                bracketed(right, matches!(**right, Condition::Or(_, _)))
             ),
-            _ => unreachable!(),
         }
     }
-
-    pub fn compute_combos(&self) -> u64 {
-        (*self.x.end() as u64 - *self.x.start() as u64 + 1)
-            * (*self.m.end() as u64 - *self.m.start() as u64 + 1)
-            * (*self.a.end() as u64 - *self.a.start() as u64 + 1)
-            * (*self.s.end() as u64 - *self.s.start() as u64 + 1)
-    }
 }
 
 #[derive(Debug)]
 pub(crate) struct Instruction {
     pub destination: String,
-    pub operator: Operator,
-    pub value: usize,
-    pub part_attribute: char,
+    pub condition: Condition,
 }
 
 impl Instruction {
-    pub fn new(
-        destination: String,
-        operator: Operator,
-        value: usize,
-        part_attribute: char,
-    ) -> Self {
-        match part_attribute {
-            'x' | 'm' | 'a' | 's' => Instruction {
-                destination,
-                operator,
-                value,
-                part_attribute,
-            },
-            _ => unreachable!(),
+    pub fn new(destination: String, condition: Condition) -> Self {
+        Instruction {
+            destination,
+            condition,
         }
     }
 
-    pub fn matches(&self, part: Part) -> bool {
-        let value_to_compare = match self.part_attribute {
-            'x' => part.x,
-            'm' => part.m,
-            'a' => part.a,
-            's' => part.s,
-            _ => unreachable!(),
-        };
-
-        match self.operator {
-            Operator::LessThan => value_to_compare < self.value,
-            Operator::GreaterThan => value_to_compare > self.value,
-        }
+    pub fn matches(&self, part: &Part) -> bool {
+        self.condition.matches(part)
     }
 }
 
@@ -196,36 +197,96 @@ impl Workflow {
     }
 }
 
+pub(crate) fn workflows_by_name(workflows: &[Workflow]) -> HashMap<&str, &Workflow> {
+    workflows.iter().map(|wf| (wf.name.as_str(), wf)).collect()
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::workflow::{Operator, PartRange};
+    use crate::hyper_rect::HyperRect;
+    use crate::workflow::{Comparison, Condition, Instruction, Operator, Part};
 
     #[test]
-    fn part_range_test() {
-        assert_eq!(4000_u64.pow(4), PartRange::default().compute_combos());
-        assert_eq!(
-            1415 * 4000 * 2005 * 1350,
-            PartRange::new(1..=1415, 1..=4000, 1..=2005, 1..=1350).compute_combos()
+    fn instruction_matches_test() {
+        let part = Part::new([787, 2655, 1222, 2876]);
+        let compare = |axis, operator, value| {
+            Box::new(Condition::Compare(Comparison::new(axis, operator, value)))
+        };
+
+        assert!(Comparison::new(2, Operator::LessThan, 2006).matches(&part));
+        assert!(!Comparison::new(3, Operator::GreaterThan, 2876).matches(&part));
+        assert!(Comparison::new(3, Operator::GreaterOrEqual, 2876).matches(&part));
+        assert!(Comparison::new(0, Operator::LessOrEqual, 787).matches(&part));
+        assert!(Comparison::new(1, Operator::Equal, 2655).matches(&part));
+        assert!(!Comparison::new(1, Operator::Equal, 2656).matches(&part));
+
+        let instruction = Instruction::new(
+            String::from("A"),
+            Condition::Or(
+                Box::new(Condition::And(
+                    compare(0, Operator::GreaterThan, 1000),
+                    compare(1, Operator::LessThan, 10),
+                )),
+                compare(2, Operator::Equal, 1222),
+            ),
         );
+
+        assert!(instruction.matches(&part));
+        assert!(!instruction.matches(&Part::new([787, 2655, 1223, 2876])));
+        assert!(instruction.matches(&Part::new([1001, 9, 1223, 2876])));
+        assert_eq!(part.sum(), 7540);
     }
 
     #[test]
-    fn part_range_split_test() {
-        let part_range = PartRange::new(1..=100, 1..=100, 1..=100, 1..=100);
+    fn comparison_split_test() {
+        let part_range = HyperRect::new([1..=100, 1..=100, 1..=100, 1..=100]);
 
         assert_eq!(
-            part_range.split_at('x', 50, Operator::LessThan),
+            Comparison::new(0, Operator::LessThan, 50).split(&part_range),
+            (
+                Some(HyperRect::new([1..=49, 1..=100, 1..=100, 1..=100])),
+                Some(HyperRect::new([50..=100, 1..=100, 1..=100, 1..=100]))
+            )
+        );
+        assert_eq!(
+            Comparison::new(0, Operator::GreaterThan, 50).split(&part_range),
+            (
+                Some(HyperRect::new([51..=100, 1..=100, 1..=100, 1..=100])),
+                Some(HyperRect::new([1..=50, 1..=100, 1..=100, 1..=100]))
+            )
+        );
+        assert_eq!(
+            Comparison::new(1, Operator::GreaterThan, 4000).split(&part_range),
+            (None, Some(part_range.clone()))
+        );
+        assert_eq!(
+            Comparison::new(0, Operator::LessOrEqual, 50).split(&part_range),
             (
-                PartRange::new(1..=49, 1..=100, 1..=100, 1..=100),
-                PartRange::new(50..=100, 1..=100, 1..=100, 1..=100)
+                Some(HyperRect::new([1..=50, 1..=100, 1..=100, 1..=100])),
+                Some(HyperRect::new([51..=100, 1..=100, 1..=100, 1..=100]))
             )
         );
         assert_eq!(
-            part_range.split_at('x', 50, Operator::GreaterThan),
+            Comparison::new(0, Operator::GreaterOrEqual, 50).split(&part_range),
             (
-                PartRange::new(1..=50, 1..=100, 1..=100, 1..=100),
-                PartRange::new(51..=100, 1..=100, 1..=100, 1..=100)
+                Some(HyperRect::new([50..=100, 1..=100, 1..=100, 1..=100])),
+                Some(HyperRect::new([1..=49, 1..=100, 1..=100, 1..=100]))
             )
         );
+        assert_eq!(
+            Comparison::new(0, Operator::LessOrEqual, u64::MAX).split(&part_range),
+            (Some(part_range.clone()), None)
+        );
+    }
+
+    #[test]
+    fn equality_is_one_sided_pair() {
+        assert_eq!(
+            Comparison::new(2, Operator::Equal, 7).to_one_sided(),
+            vec![
+                Comparison::new(2, Operator::GreaterOrEqual, 7),
+                Comparison::new(2, Operator::LessOrEqual, 7)
+            ]
+        );
     }
 }
@@ -0,0 +1,164 @@
+use std::collections::BTreeMap;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct CubeSet {
+    counts: BTreeMap<String, u32>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CubeSetError {
+    ParseError(ParseIntError),
+    InvalidCubes(String),
+    DuplicateColour(String),
+}
+
+impl From<ParseIntError> for CubeSetError {
+    fn from(err: ParseIntError) -> Self {
+        CubeSetError::ParseError(err)
+    }
+}
+
+impl CubeSet {
+    pub fn new() -> Self {
+        CubeSet {
+            counts: BTreeMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, colour: &str, count: u32) {
+        self.counts.insert(colour.to_string(), count);
+    }
+
+    pub fn get(&self, colour: &str) -> u32 {
+        self.counts.get(colour).copied().unwrap_or(0)
+    }
+
+    pub fn colours(&self) -> impl Iterator<Item = &str> {
+        self.counts.keys().map(|colour| colour.as_str())
+    }
+
+    // Whether every draw of `other` could have come out of this set; colours this set has
+    // never heard of count as zero cubes
+    pub fn is_superset_of(&self, other: &CubeSet) -> bool {
+        other
+            .counts
+            .iter()
+            .all(|(colour, &count)| self.get(colour) >= count)
+    }
+
+    pub fn min_covering<'a>(sets: impl IntoIterator<Item = &'a CubeSet>) -> CubeSet {
+        sets.into_iter().fold(CubeSet::new(), |mut covering, set| {
+            for (colour, &count) in set.counts.iter() {
+                let entry = covering.counts.entry(colour.clone()).or_insert(0);
+                *entry = (*entry).max(count);
+            }
+            covering
+        })
+    }
+
+    // Product of this set's counts over every colour of `bag`, so a colour the set lacks
+    // makes the power zero
+    pub fn power(&self, bag: &CubeSet) -> u64 {
+        bag.colours()
+            .map(|colour| self.get(colour) as u64)
+            .product()
+    }
+}
+
+// Parses draws in the puzzle's form, e.g. `3 blue, 4 red`
+impl FromStr for CubeSet {
+    type Err = CubeSetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cube_set = CubeSet::new();
+
+        for cubes_str in s.split(',').map(|part| part.trim()) {
+            let mut splitted = cubes_str.split_whitespace();
+
+            let (Some(num_str), Some(colour), None) =
+                (splitted.next(), splitted.next(), splitted.next())
+            else {
+                return Err(CubeSetError::InvalidCubes(cubes_str.to_string()));
+            };
+
+            if cube_set.counts.contains_key(colour) {
+                return Err(CubeSetError::DuplicateColour(colour.to_string()));
+            }
+
+            cube_set.insert(colour, num_str.parse::<u32>()?);
+        }
+
+        Ok(cube_set)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cube_set::{CubeSet, CubeSetError};
+
+    #[test]
+    fn parse_cube_sets() {
+        let set: CubeSet = "3 blue, 4 red".parse().unwrap();
+
+        assert_eq!(set.get("blue"), 3);
+        assert_eq!(set.get("red"), 4);
+        assert_eq!(set.get("green"), 0);
+        assert_eq!(set.colours().collect::<Vec<_>>(), vec!["blue", "red"]);
+
+        assert_eq!(
+            "3 blue, red".parse::<CubeSet>(),
+            Err(CubeSetError::InvalidCubes(String::from("red")))
+        );
+        assert_eq!(
+            "3 blue, 4 blue".parse::<CubeSet>(),
+            Err(CubeSetError::DuplicateColour(String::from("blue")))
+        );
+        assert!(matches!(
+            "-3 blue".parse::<CubeSet>(),
+            Err(CubeSetError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn superset_with_large_counts() {
+        let bag: CubeSet = "12 red, 13 green, 14 blue, 500 violet".parse().unwrap();
+
+        assert!(bag.is_superset_of(&"20 violet, 12 red".parse().unwrap()));
+        assert!(bag.is_superset_of(&"500 violet".parse().unwrap()));
+        assert!(!bag.is_superset_of(&"200 blue".parse().unwrap()));
+        assert!(!bag.is_superset_of(&"1 orange".parse().unwrap()));
+        assert!(bag.is_superset_of(&"0 orange".parse().unwrap()));
+    }
+
+    #[test]
+    fn min_covering_and_power() {
+        let bag: CubeSet = "12 red, 13 green, 14 blue".parse().unwrap();
+        let draws: Vec<CubeSet> = ["6 red, 1 blue, 3 green", "2 blue, 1 red, 2 green"]
+            .iter()
+            .map(|draw| draw.parse().unwrap())
+            .collect();
+
+        let covering = CubeSet::min_covering(draws.iter());
+
+        assert_eq!(covering, "6 red, 2 blue, 3 green".parse().unwrap());
+        assert_eq!(covering.power(&bag), 36);
+        assert_eq!(CubeSet::min_covering([]).power(&bag), 0);
+    }
+
+    #[test]
+    fn power_of_a_game_missing_a_colour() {
+        let bag: CubeSet = "12 red, 13 green, 14 blue".parse().unwrap();
+        let draws: Vec<CubeSet> = ["6 red, 3 green", "1 red, 2 green"]
+            .iter()
+            .map(|draw| draw.parse().unwrap())
+            .collect();
+
+        assert_eq!(CubeSet::min_covering(draws.iter()).power(&bag), 0);
+        assert_eq!(
+            CubeSet::min_covering(draws.iter()).power(&"12 red, 13 green".parse().unwrap()),
+            18
+        );
+    }
+}
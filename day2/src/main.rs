@@ -1,27 +1,43 @@
-mod sack;
+mod cube_set;
 
+use crate::cube_set::CubeSet;
 use common::filereader;
 use regex::Regex;
-use sack::Sack;
-
-const REFERENCE_SACK: Sack = Sack::new(12, 13, 14);
-
-fn is_valid(sacks: Vec<Sack>) -> bool {
-    REFERENCE_SACK.is_valid(&sacks)
+use std::env;
+use std::path::Path;
+
+const DEFAULT_BAG: &str = "12 red, 13 green, 14 blue";
+
+// The bag can be given as the first argument, either inline (`"12 red, 13 green"`) or as a
+// path to a file holding it on its first line
+fn read_reference_bag() -> CubeSet {
+    let bag_str = match env::args().nth(1) {
+        Some(arg) if Path::new(&arg).is_file() => match filereader::read_file(&arg) {
+            Ok(lines) => lines.into_iter().next().unwrap_or_default(),
+            Err(e) => panic!("{}", e),
+        },
+        Some(arg) => arg,
+        None => String::from(DEFAULT_BAG),
+    };
+
+    match bag_str.parse() {
+        Ok(bag) => bag,
+        Err(e) => panic!("Bad reference bag {}: {:?}", bag_str, e),
+    }
 }
 
-fn parse_to_sacks(line: &String) -> (u8, Vec<Sack>) {
-    let sack_regex = Regex::new(r"^Game (\d+): (.*)$").unwrap();
+fn parse_game(line: &String) -> (u32, Vec<CubeSet>) {
+    let game_regex = Regex::new(r"^Game (\d+): (.*)$").unwrap();
 
-    if let Some(caps) = sack_regex.captures(line) {
-        let game_number = caps.get(1).unwrap().as_str().parse::<u8>().unwrap();
-        let sack_sequence = caps.get(2).unwrap().as_str();
+    if let Some(caps) = game_regex.captures(line) {
+        let game_number = caps.get(1).unwrap().as_str().parse::<u32>().unwrap();
+        let draw_sequence = caps.get(2).unwrap().as_str();
 
         return (
             game_number,
-            sack_sequence
+            draw_sequence
                 .split("; ")
-                .map(|sack_str| Sack::from(sack_str).unwrap())
+                .map(|draw_str| draw_str.parse().unwrap())
                 .collect(),
         );
     }
@@ -29,41 +45,83 @@ fn parse_to_sacks(line: &String) -> (u8, Vec<Sack>) {
     panic!("Bad input string: {}", line);
 }
 
-fn solve_line(line: &String) -> u8 {
-    let mut result: u8 = 0;
+fn solve_line(line: &String, bag: &CubeSet) -> u32 {
+    let (game_number, draws) = parse_game(line);
 
-    let (game_number, sacks) = parse_to_sacks(line);
-
-    if is_valid(sacks) {
-        result += game_number;
+    if draws.iter().all(|draw| bag.is_superset_of(draw)) {
+        game_number
+    } else {
+        0
     }
-
-    result
 }
 
-fn solve_line_min(line: &String) -> u32 {
-    let (_, sacks) = parse_to_sacks(line);
-    let min_sack = Sack::get_min_sack(sacks);
+fn solve_line_min(line: &String, bag: &CubeSet) -> u64 {
+    let (_, draws) = parse_game(line);
 
-    min_sack.power() as u32
+    CubeSet::min_covering(draws.iter()).power(bag)
 }
 
-fn solve(lines: Vec<String>) -> u32 {
+fn solve(lines: Vec<String>, bag: &CubeSet) -> u32 {
     lines
         .iter()
-        .fold(0, |acc, line| acc + solve_line(line) as u32)
+        .fold(0, |acc, line| acc + solve_line(line, bag))
 }
 
-fn solve_min(lines: Vec<String>) -> u32 {
-    lines.iter().fold(0, |acc, line| acc + solve_line_min(line))
+fn solve_min(lines: Vec<String>, bag: &CubeSet) -> u64 {
+    lines
+        .iter()
+        .fold(0, |acc, line| acc + solve_line_min(line, bag))
 }
 
 fn main() {
+    let bag = read_reference_bag();
+
     match filereader::read_file("./day2/resources/input.txt") {
         Ok(lines) => {
-            let result = solve_min(lines);
-            println!("{:?}", result)
+            println!("{:?}", solve(lines.clone(), &bag));
+            println!("{:?}", solve_min(lines, &bag))
         }
         Err(e) => panic!("{}", e),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{solve, solve_min};
+
+    fn example() -> Vec<String> {
+        vec![
+            String::from("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green"),
+            String::from("Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue"),
+            String::from(
+                "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red",
+            ),
+            String::from(
+                "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red",
+            ),
+            String::from("Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"),
+        ]
+    }
+
+    #[test]
+    fn example_games() {
+        assert_eq!(
+            solve(example(), &"12 red, 13 green, 14 blue".parse().unwrap()),
+            8
+        );
+        assert_eq!(
+            solve(example(), &"20 red, 13 green, 15 blue".parse().unwrap()),
+            15
+        );
+        assert_eq!(
+            solve_min(example(), &"12 red, 13 green, 14 blue".parse().unwrap()),
+            2286
+        );
+        // A game that never draws blue needs no blue cubes, so its power is zero
+        let no_blue = vec![String::from("Game 1: 3 red, 2 green; 1 red, 4 green")];
+        assert_eq!(
+            solve_min(no_blue, &"12 red, 13 green, 14 blue".parse().unwrap()),
+            0
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use crate::get_neighbors;
+use std::collections::VecDeque;
+
+// Plots of a single garden tile the elf can end on after a given number of steps, worked out
+// once from the shortest walking distance of every plot to a start point
+pub struct GardenDistances {
+    // reachable_by_parity[p][d] counts the plots first reached at a distance of at most d
+    // with parity p, which are exactly the plots the elf can end on after d steps
+    reachable_by_parity: [Vec<u64>; 2],
+}
+
+impl GardenDistances {
+    pub fn new(grid: &[Vec<char>], start: (usize, usize)) -> Self {
+        let mut distances = vec![vec![None; grid[0].len()]; grid.len()];
+        let mut queue = VecDeque::new();
+
+        distances[start.0][start.1] = Some(0);
+        queue.push_back(start);
+
+        while let Some((r, c)) = queue.pop_front() {
+            let dist = distances[r][c].unwrap();
+
+            for (new_row, new_col) in get_neighbors(grid, (r, c)) {
+                if grid[new_row][new_col] != '#' && distances[new_row][new_col].is_none() {
+                    distances[new_row][new_col] = Some(dist + 1);
+                    queue.push_back((new_row, new_col));
+                }
+            }
+        }
+
+        let max_distance = distances
+            .iter()
+            .flatten()
+            .flatten()
+            .max()
+            .copied()
+            .unwrap_or(0);
+        let mut counts = vec![0_u64; max_distance + 1];
+
+        for &dist in distances.iter().flatten().flatten() {
+            counts[dist] += 1;
+        }
+
+        let mut reachable_by_parity =
+            [vec![0_u64; max_distance + 1], vec![0_u64; max_distance + 1]];
+
+        for (dist, &count) in counts.iter().enumerate() {
+            for (parity, reachable) in reachable_by_parity.iter_mut().enumerate() {
+                let previous = if dist > 0 { reachable[dist - 1] } else { 0 };
+
+                reachable[dist] = if dist % 2 == parity {
+                    previous + count
+                } else {
+                    previous
+                };
+            }
+        }
+
+        GardenDistances {
+            reachable_by_parity,
+        }
+    }
+
+    pub fn get_max_distance(&self) -> usize {
+        self.reachable_by_parity[0].len() - 1
+    }
+
+    pub fn count_reachable(&self, steps: usize) -> u64 {
+        self.reachable_by_parity[steps % 2][steps.min(self.get_max_distance())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bfs_propagate;
+    use crate::distances::GardenDistances;
+    use crate::tests::example_grid;
+
+    #[test]
+    fn distances_from_start() {
+        let distances = GardenDistances::new(&example_grid(), (5, 5));
+
+        assert_eq!(distances.count_reachable(0), 1);
+        assert_eq!(distances.count_reachable(1), 2);
+        assert_eq!(distances.count_reachable(6), 16);
+    }
+
+    #[test]
+    fn matches_bfs_for_every_step_count() {
+        let grid = example_grid();
+
+        for start in [(5, 5), (0, 0), (10, 5), (3, 10)] {
+            let distances = GardenDistances::new(&grid, start);
+
+            for steps in 0..=distances.get_max_distance() + 3 {
+                assert_eq!(
+                    distances.count_reachable(steps),
+                    bfs_propagate(&grid, start, steps as isize) as u64,
+                    "{} steps from {:?}",
+                    steps,
+                    start
+                );
+            }
+        }
+    }
+}
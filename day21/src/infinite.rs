@@ -0,0 +1,300 @@
+use std::collections::HashSet;
+
+// How many consecutive equal second differences we want to see before trusting the fit
+const STABLE_SAMPLES: usize = 3;
+const MAX_SAMPLES: usize = 64;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReachError {
+    // The counts sampled every period didn't settle into a quadratic within MAX_SAMPLES
+    NeverSettled,
+    Overflow,
+}
+
+// The garden repeated forever in every direction, with the start point in the tile at (0, 0)
+pub struct InfiniteGarden {
+    grid: Vec<Vec<char>>,
+    start: (usize, usize),
+}
+
+impl InfiniteGarden {
+    pub fn new(grid: Vec<Vec<char>>, start: (usize, usize)) -> Self {
+        InfiniteGarden { grid, start }
+    }
+
+    // Steps after which the tiling looks the same again. An odd period would flip the
+    // parity of every tile between samples, so it gets doubled.
+    pub fn get_period(&self) -> u64 {
+        let rows = self.grid.len() as u64;
+        let cols = self.grid[0].len() as u64;
+        let period = rows / gcd(rows, cols) * cols;
+
+        if period % 2 == 1 {
+            period * 2
+        } else {
+            period
+        }
+    }
+
+    fn is_plot(&self, (r, c): (i64, i64)) -> bool {
+        let row = r.rem_euclid(self.grid.len() as i64) as usize;
+        let col = c.rem_euclid(self.grid[0].len() as i64) as usize;
+
+        self.grid[row][col] != '#'
+    }
+
+    // Number of plots the elf can be standing on after exactly `steps` steps. Counts are
+    // walked out directly until the samples taken every period settle into a quadratic,
+    // which is then extrapolated; small step counts never get that far.
+    pub fn count_reachable(&self, steps: u64) -> Result<u64, ReachError> {
+        let period = self.get_period();
+        let offset = steps % period;
+        let target_sample = steps / period;
+
+        let mut walker = Walker::new(self);
+        let mut samples: Vec<u64> = Vec::new();
+
+        loop {
+            let sample_steps = offset + samples.len() as u64 * period;
+            walker.walk_to(sample_steps);
+
+            if sample_steps == steps {
+                return Ok(walker.count_reachable());
+            }
+
+            samples.push(walker.count_reachable());
+
+            if let Some(fit) = Quadratic::fit(&samples) {
+                return fit
+                    .extrapolate(target_sample - (samples.len() as u64 - 1))
+                    .ok_or(ReachError::Overflow);
+            }
+
+            if samples.len() >= MAX_SAMPLES {
+                return Err(ReachError::NeverSettled);
+            }
+        }
+    }
+
+    // Walks every step out directly, for when the counts can't be extrapolated
+    pub fn simulate(&self, steps: u64) -> u64 {
+        let mut walker = Walker::new(self);
+        walker.walk_to(steps);
+
+        walker.count_reachable()
+    }
+}
+
+// Breadth-first walk over the infinite tiling, one step at a time. A plot first reached
+// at distance d can be stood on at every later step with the same parity as d.
+struct Walker<'a> {
+    garden: &'a InfiniteGarden,
+    visited: HashSet<(i64, i64)>,
+    frontier: Vec<(i64, i64)>,
+    reached_by_parity: [u64; 2],
+    steps: u64,
+}
+
+impl<'a> Walker<'a> {
+    fn new(garden: &'a InfiniteGarden) -> Self {
+        let start = (garden.start.0 as i64, garden.start.1 as i64);
+
+        Walker {
+            garden,
+            visited: HashSet::from([start]),
+            frontier: vec![start],
+            reached_by_parity: [1, 0],
+            steps: 0,
+        }
+    }
+
+    fn walk_to(&mut self, steps: u64) {
+        while self.steps < steps {
+            let mut next_frontier = Vec::new();
+
+            for &(r, c) in self.frontier.iter() {
+                for neighbor in [(r - 1, c), (r + 1, c), (r, c - 1), (r, c + 1)] {
+                    if self.garden.is_plot(neighbor) && self.visited.insert(neighbor) {
+                        next_frontier.push(neighbor);
+                    }
+                }
+            }
+
+            self.steps += 1;
+            self.reached_by_parity[(self.steps % 2) as usize] += next_frontier.len() as u64;
+            self.frontier = next_frontier;
+        }
+    }
+
+    fn count_reachable(&self) -> u64 {
+        self.reached_by_parity[(self.steps % 2) as usize]
+    }
+}
+
+// f(t) = last + t * slope + second * t * (t + 1) / 2, continuing on from the final sample
+struct Quadratic {
+    last: i128,
+    slope: i128,
+    second: i128,
+}
+
+impl Quadratic {
+    fn fit(samples: &[u64]) -> Option<Self> {
+        if samples.len() < STABLE_SAMPLES + 2 {
+            return None;
+        }
+
+        let values: Vec<i128> = samples.iter().map(|&s| s as i128).collect();
+        let firsts: Vec<i128> = values.windows(2).map(|w| w[1] - w[0]).collect();
+        let seconds: Vec<i128> = firsts.windows(2).map(|w| w[1] - w[0]).collect();
+
+        let tail = &seconds[seconds.len() - STABLE_SAMPLES..];
+
+        if tail.iter().any(|&second| second != tail[0]) {
+            return None;
+        }
+
+        Some(Quadratic {
+            last: values[values.len() - 1],
+            slope: firsts[firsts.len() - 1],
+            second: tail[0],
+        })
+    }
+
+    fn extrapolate(&self, t: u64) -> Option<u64> {
+        let t = i128::from(t);
+        let linear = t.checked_mul(self.slope)?;
+        let quadratic = self.second.checked_mul(t)?.checked_mul(t.checked_add(1)?)? / 2;
+
+        u64::try_from(self.last.checked_add(linear)?.checked_add(quadratic)?).ok()
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bfs_propagate;
+    use crate::infinite::{InfiniteGarden, ReachError, STABLE_SAMPLES};
+    use crate::tests::example_grid;
+
+    // Lays enough copies of the grid around the original for `steps` to never reach the edge
+    fn wrapped_grid(grid: &[Vec<char>], steps: usize) -> (Vec<Vec<char>>, usize) {
+        let repeats = steps / grid.len().min(grid[0].len()) + 1;
+        let copies = 2 * repeats + 1;
+
+        let wrapped = (0..grid.len() * copies)
+            .map(|r| {
+                (0..grid[0].len() * copies)
+                    .map(|c| grid[r % grid.len()][c % grid[0].len()])
+                    .collect()
+            })
+            .collect();
+
+        (wrapped, repeats)
+    }
+
+    #[test]
+    fn matches_puzzle_examples() {
+        let garden = InfiniteGarden::new(example_grid(), (5, 5));
+
+        for (steps, expected) in [
+            (6, 16),
+            (10, 50),
+            (50, 1594),
+            (100, 6536),
+            (500, 167004),
+            (1000, 668697),
+            (5000, 16733044),
+        ] {
+            assert_eq!(
+                garden.count_reachable(steps),
+                Ok(expected),
+                "{} steps",
+                steps
+            );
+        }
+    }
+
+    #[test]
+    fn matches_bfs_on_wrapped_grid() {
+        let grid = example_grid();
+        let garden = InfiniteGarden::new(grid.clone(), (5, 5));
+
+        for steps in [1, 7, 22, 65, 131] {
+            let (wrapped, repeats) = wrapped_grid(&grid, steps);
+            let start = (repeats * grid.len() + 5, repeats * grid[0].len() + 5);
+
+            assert_eq!(
+                garden.count_reachable(steps as u64).unwrap(),
+                bfs_propagate(&wrapped, start, steps as isize) as u64,
+                "{} steps",
+                steps
+            );
+        }
+    }
+
+    #[test]
+    fn extrapolation_matches_direct_walk() {
+        let garden = InfiniteGarden::new(example_grid(), (5, 5));
+        let period = garden.get_period();
+
+        // Far enough out that every count comes from the fitted quadratic rather than
+        // from a sample the walker took
+        for steps in [155, 221, 290, 333, 412] {
+            assert!(steps / period > STABLE_SAMPLES as u64 + 1);
+
+            assert_eq!(
+                garden.count_reachable(steps),
+                Ok(garden.simulate(steps)),
+                "{} steps",
+                steps
+            );
+        }
+    }
+
+    #[test]
+    fn non_square_and_enclosed_gardens() {
+        let grid: Vec<Vec<char>> = ["..#..", ".....", "#.S.#"]
+            .iter()
+            .map(|line| line.chars().collect())
+            .collect();
+        let garden = InfiniteGarden::new(grid.clone(), (2, 2));
+
+        assert_eq!(garden.get_period(), 30);
+
+        for steps in [3, 40, 150] {
+            let (wrapped, repeats) = wrapped_grid(&grid, steps);
+            let start = (repeats * grid.len() + 2, repeats * grid[0].len() + 2);
+
+            assert_eq!(
+                garden.count_reachable(steps as u64).unwrap(),
+                bfs_propagate(&wrapped, start, steps as isize) as u64
+            );
+        }
+
+        let boxed: Vec<Vec<char>> = ["#####", "#.S.#", "#####"]
+            .iter()
+            .map(|line| line.chars().collect())
+            .collect();
+        let boxed_garden = InfiniteGarden::new(boxed, (1, 2));
+
+        assert_eq!(boxed_garden.count_reachable(1_000_000), Ok(1));
+        assert_eq!(boxed_garden.count_reachable(1_000_001), Ok(2));
+    }
+
+    #[test]
+    fn reports_counts_that_overflow() {
+        // Every plot is open, so (steps + 1)^2 plots are reachable
+        let open = InfiniteGarden::new(vec![vec!['.']], (0, 0));
+
+        assert_eq!(open.count_reachable(1_000_000), Ok(1_000_001 * 1_000_001));
+        assert_eq!(open.count_reachable(1 << 40), Err(ReachError::Overflow));
+    }
+}
@@ -1,8 +1,12 @@
-use common::{filereader, filewriter};
+mod distances;
+mod infinite;
+
+use crate::distances::GardenDistances;
+use crate::infinite::{InfiniteGarden, ReachError};
+use common::filereader;
 use std::cell::Cell;
-use std::collections::{HashSet, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::ops::Div;
-use std::time::Instant;
 
 pub struct CellFillRequest {
     start_coords: (usize, usize),
@@ -20,28 +24,7 @@ impl CellFillRequest {
     }
 }
 
-fn print_grid(grid: &Vec<Vec<char>>, coords: &HashSet<(usize, usize)>) {
-    let mut matrix = vec![vec!['.'; grid[0].len()]; grid.len()];
-
-    for i in 0..matrix.len() {
-        for j in 0..matrix[i].len() {
-            if coords.contains(&(i, j)) {
-                matrix[i][j] = 'O';
-            } else {
-                matrix[i][j] = grid[i][j];
-            }
-        }
-    }
-
-    let to_print: Vec<String> = matrix
-        .iter()
-        .map(|r| r.iter().fold(String::new(), |acc, &c| format!("{acc}{c}")))
-        .collect();
-
-    filewriter::write_file("./day21/resources/output.txt", to_print);
-}
-
-fn get_neighbors(grid: &Vec<Vec<char>>, coords: (usize, usize)) -> Vec<(usize, usize)> {
+fn get_neighbors(grid: &[Vec<char>], coords: (usize, usize)) -> Vec<(usize, usize)> {
     let (r, c) = coords;
     let mut neighbors = Vec::with_capacity(8);
 
@@ -83,8 +66,6 @@ fn find_coords_where(grid: &Vec<Vec<char>>, pred: fn(char) -> bool) -> Option<(u
 }
 
 fn bfs_propagate(grid: &Vec<Vec<char>>, start: (usize, usize), distance: isize) -> usize {
-    let timer = Instant::now();
-
     let mut visited = HashSet::new();
     let mut queue = VecDeque::new();
     queue.push_back((start.0, start.1, 0));
@@ -110,16 +91,9 @@ fn bfs_propagate(grid: &Vec<Vec<char>>, start: (usize, usize), distance: isize)
         }
     }
 
-    let mut heap_coords: HashSet<(usize, usize)> =
-        queue.into_iter().map(|(r, c, _)| (r, c)).collect();
+    let heap_coords: HashSet<(usize, usize)> = queue.into_iter().map(|(r, c, _)| (r, c)).collect();
 
-    let heap_len = heap_coords.len();
-
-    print_grid(grid, &heap_coords.into_iter().collect());
-
-    println!("Time elapsed: {:?}", timer.elapsed());
-
-    heap_len
+    heap_coords.len()
 }
 
 fn solve(lines: Vec<String>) -> u32 {
@@ -135,14 +109,33 @@ fn solve(lines: Vec<String>) -> u32 {
     unreachable!()
 }
 
-fn solve2(lines: Vec<String>) -> u64 {
-    let grid: Vec<Vec<char>> = lines
-        .into_iter()
-        .map(|line| line.chars().collect())
-        .collect();
+// Tile filling only holds for a square grid with S at the centre, clear lanes along its row,
+// its column and the grid's border, and `steps` landing exactly on the edge of a tile
+fn tiles_fill_cleanly(grid: &[Vec<char>], start: (usize, usize), steps: usize) -> bool {
+    let size = grid.len();
+    let is_clear = |(r, c): (usize, usize)| grid[r][c] != '#';
+
+    grid.iter().all(|row| row.len() == size)
+        && size % 2 == 1
+        && start == (size / 2, size / 2)
+        && steps % size == size / 2
+        && steps / size >= 2
+        && (0..size).all(|i| {
+            [
+                (start.0, i),
+                (i, start.1),
+                (0, i),
+                (size - 1, i),
+                (i, 0),
+                (i, size - 1),
+            ]
+            .into_iter()
+            .all(is_clear)
+        })
+}
 
-    let size = /*11_usize;*/  grid.len();
-    let steps = /*49_usize; */ 26501365;
+fn fill_tiles(grid: &[Vec<char>], steps: usize) -> u64 {
+    let size = grid.len();
 
     let total_grid_width = steps.div(size) - 1;
     let even_tiles = usize::pow(total_grid_width.div(2) * 2 + 1, 2);
@@ -193,23 +186,53 @@ fn solve2(lines: Vec<String>) -> u64 {
         CellFillRequest::new(bottom_right_coords, lil_tiles, small_diagonal_steps),
     ];
 
+    // Several requests share a start point, so each distance map is only built once
+    let mut distances_by_start: HashMap<(usize, usize), GardenDistances> = HashMap::new();
     let mut total = 0_u64;
 
-    if let Some((row, col)) = find_coords_where(&grid, |c| c == 'S') {
-        for CellFillRequest {
-            start_coords,
-            num_cells,
-            steps,
-        } in cell_requests
-        {
-            let available_spots = bfs_propagate(&grid, start_coords, steps as isize);
-            total += (available_spots as u64) * (num_cells as u64);
-        }
+    for CellFillRequest {
+        start_coords,
+        num_cells,
+        steps,
+    } in cell_requests
+    {
+        let available_spots = distances_by_start
+            .entry(start_coords)
+            .or_insert_with(|| GardenDistances::new(grid, start_coords))
+            .count_reachable(steps);
+        total += available_spots * (num_cells as u64);
     }
 
     total
 }
 
+fn count_reachable(grid: Vec<Vec<char>>, start: (usize, usize), steps: usize) -> u64 {
+    if tiles_fill_cleanly(&grid, start, steps) {
+        fill_tiles(&grid, steps)
+    } else {
+        let garden = InfiniteGarden::new(grid, start);
+
+        match garden.count_reachable(steps as u64) {
+            Ok(count) => count,
+            Err(ReachError::NeverSettled) => garden.simulate(steps as u64),
+            Err(e) => panic!("Can't count plots reachable in {} steps: {:?}", steps, e),
+        }
+    }
+}
+
+fn solve2(lines: Vec<String>) -> u64 {
+    let grid: Vec<Vec<char>> = lines
+        .into_iter()
+        .map(|line| line.chars().collect())
+        .collect();
+
+    if let Some(start) = find_coords_where(&grid, |c| c == 'S') {
+        return count_reachable(grid, start, 26501365);
+    }
+
+    unreachable!()
+}
+
 fn main() {
     match filereader::read_file("./day21/resources/input.txt") {
         Ok(lines) => {
@@ -222,9 +245,29 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
-    use crate::{bfs_propagate, find_coords_where};
+    use crate::infinite::InfiniteGarden;
+    use crate::{bfs_propagate, fill_tiles, find_coords_where, solve2, tiles_fill_cleanly};
     use common::filereader;
 
+    pub(crate) fn example_grid() -> Vec<Vec<char>> {
+        [
+            "...........",
+            ".....###.#.",
+            ".###.##..#.",
+            "..#.#...#..",
+            "....#.#....",
+            ".##..S####.",
+            ".##..#...#.",
+            ".......##..",
+            ".##.#.####.",
+            ".##..##.##.",
+            "...........",
+        ]
+        .iter()
+        .map(|line| line.chars().collect())
+        .collect()
+    }
+
     #[test]
     fn bfs_example() {
         let grid = vec![
@@ -260,4 +303,39 @@ mod tests {
             Err(e) => panic!("{}", e),
         }
     }
+
+    #[test]
+    fn infinite_part_2() {
+        match filereader::read_file("../day21/resources/input.txt") {
+            Ok(lines) => assert_eq!(solve2(lines), 593174122420825),
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    #[test]
+    fn tile_filling_matches_infinite_solver() {
+        match filereader::read_file("../day21/resources/input.txt") {
+            Ok(lines) => {
+                let grid: Vec<Vec<char>> =
+                    lines.iter().map(|line| line.chars().collect()).collect();
+
+                let start = find_coords_where(&grid, |c| c == 'S').unwrap();
+
+                assert!(tiles_fill_cleanly(&grid, start, 26501365));
+                assert_eq!(
+                    fill_tiles(&grid, 26501365),
+                    InfiniteGarden::new(grid, start)
+                        .count_reachable(26501365)
+                        .unwrap()
+                );
+            }
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    #[test]
+    fn example_does_not_fill_cleanly() {
+        // The row through S is blocked, so the example has to go through the general solver
+        assert!(!tiles_fill_cleanly(&example_grid(), (5, 5), 5 + 11 * 4));
+    }
 }
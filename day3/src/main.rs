@@ -1,14 +1,85 @@
 mod part1;
 mod part2;
+mod schematic;
 
+use crate::schematic::Schematic;
 use common::filereader;
+use std::env;
+
+// One line per symbol of `kind` touching exactly `count` numbers, e.g. `* at 1,3: 467 35`
+fn describe_symbols(lines: &[String], kind: char, count: usize) -> Vec<String> {
+    let schematic = Schematic::parse(lines);
+    let symbols = schematic.get_symbols();
+    let numbers = schematic.get_numbers();
+
+    schematic
+        .symbols_with_neighbours(kind, count)
+        .map(|idx| {
+            let values: Vec<String> = schematic
+                .numbers_adjacent_to(idx)
+                .iter()
+                .map(|&number| numbers[number].value.to_string())
+                .collect();
+
+            format!(
+                "{} at {},{}: {}",
+                symbols[idx].kind,
+                symbols[idx].row,
+                symbols[idx].col,
+                values.join(" ")
+            )
+        })
+        .collect()
+}
+
+// `--symbols K N` lists every symbol K adjacent to exactly N numbers
+fn symbol_query() -> Option<(char, usize)> {
+    let args: Vec<String> = env::args().collect();
+    let flag = args.iter().position(|arg| arg == "--symbols")?;
+
+    let kind = match args
+        .get(flag + 1)
+        .map(|arg| arg.chars().collect::<Vec<char>>())
+    {
+        Some(chars) if chars.len() == 1 => chars[0],
+        _ => panic!("--symbols needs a single symbol character"),
+    };
+    let count = match args.get(flag + 2).map(|arg| arg.parse::<usize>()) {
+        Some(Ok(count)) => count,
+        _ => panic!("--symbols needs a number of adjacent numbers"),
+    };
+
+    Some((kind, count))
+}
 
 fn main() {
     match filereader::read_file("./day3/resources/input.txt") {
         Ok(lines) => {
+            if let Some((kind, count)) = symbol_query() {
+                for line in describe_symbols(&lines, kind, count) {
+                    println!("{}", line);
+                }
+            }
             let result = part2::solve(lines);
             println!("{:?}", result)
         }
         Err(e) => panic!("{}", e),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::describe_symbols;
+
+    #[test]
+    fn describes_matching_symbols() {
+        let lines: Vec<String> = ["467..114..", "...*......", "..35..633.", "......#..."]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+
+        assert_eq!(describe_symbols(&lines, '*', 2), vec!["* at 1,3: 467 35"]);
+        assert_eq!(describe_symbols(&lines, '#', 1), vec!["# at 3,6: 633"]);
+        assert!(describe_symbols(&lines, '#', 3).is_empty());
+    }
+}
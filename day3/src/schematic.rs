@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PartNumber {
+    pub value: u32,
+    pub row: usize,
+    pub start_col: usize,
+    pub end_col: usize,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Symbol {
+    pub kind: char,
+    pub row: usize,
+    pub col: usize,
+}
+
+// Numbers and symbols are referred to by their index into `numbers` and `symbols`; the two
+// adjacency lists mirror each other
+pub struct Schematic {
+    numbers: Vec<PartNumber>,
+    symbols: Vec<Symbol>,
+    number_to_symbols: Vec<Vec<usize>>,
+    symbol_to_numbers: Vec<Vec<usize>>,
+}
+
+impl Schematic {
+    pub fn parse(lines: &[String]) -> Self {
+        let mut numbers = Vec::new();
+        let mut symbols = Vec::new();
+
+        for (row, line) in lines.iter().enumerate() {
+            let chars: Vec<char> = line.chars().collect();
+            let mut col = 0;
+
+            while col < chars.len() {
+                let c = chars[col];
+
+                if c.is_ascii_digit() {
+                    let start_col = col;
+                    let mut value = 0;
+
+                    while col < chars.len() && chars[col].is_ascii_digit() {
+                        value = value * 10 + chars[col].to_digit(10).unwrap();
+                        col += 1;
+                    }
+
+                    numbers.push(PartNumber {
+                        value,
+                        row,
+                        start_col,
+                        end_col: col - 1,
+                    });
+                    continue;
+                }
+
+                if c != '.' {
+                    symbols.push(Symbol { kind: c, row, col });
+                }
+
+                col += 1;
+            }
+        }
+
+        let symbols_by_coords: HashMap<(usize, usize), usize> = symbols
+            .iter()
+            .enumerate()
+            .map(|(idx, symbol)| ((symbol.row, symbol.col), idx))
+            .collect();
+
+        let mut number_to_symbols = vec![Vec::new(); numbers.len()];
+        let mut symbol_to_numbers = vec![Vec::new(); symbols.len()];
+
+        for (number_idx, number) in numbers.iter().enumerate() {
+            // The ring of cells around the number, clipped at the top and left edges; cells
+            // past the bottom or right edge simply never hold a symbol
+            for r in number.row.saturating_sub(1)..=number.row + 1 {
+                for c in number.start_col.saturating_sub(1)..=number.end_col + 1 {
+                    if let Some(&symbol_idx) = symbols_by_coords.get(&(r, c)) {
+                        number_to_symbols[number_idx].push(symbol_idx);
+                        symbol_to_numbers[symbol_idx].push(number_idx);
+                    }
+                }
+            }
+        }
+
+        Schematic {
+            numbers,
+            symbols,
+            number_to_symbols,
+            symbol_to_numbers,
+        }
+    }
+
+    pub fn get_numbers(&self) -> &[PartNumber] {
+        &self.numbers
+    }
+
+    pub fn get_symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    pub fn symbols_adjacent_to(&self, number_idx: usize) -> &[usize] {
+        &self.number_to_symbols[number_idx]
+    }
+
+    pub fn numbers_adjacent_to(&self, symbol_idx: usize) -> &[usize] {
+        &self.symbol_to_numbers[symbol_idx]
+    }
+
+    // Numbers touching at least one symbol of any kind
+    pub fn part_numbers(&self) -> impl Iterator<Item = &PartNumber> {
+        self.numbers
+            .iter()
+            .enumerate()
+            .filter(|&(idx, _)| !self.symbols_adjacent_to(idx).is_empty())
+            .map(|(_, number)| number)
+    }
+
+    // Indices of symbols of the given kind that touch exactly `count` numbers
+    pub fn symbols_with_neighbours(
+        &self,
+        kind: char,
+        count: usize,
+    ) -> impl Iterator<Item = usize> + '_ {
+        self.symbols
+            .iter()
+            .enumerate()
+            .filter(move |&(idx, symbol)| {
+                symbol.kind == kind && self.symbol_to_numbers[idx].len() == count
+            })
+            .map(|(idx, _)| idx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::schematic::Schematic;
+
+    fn example() -> Vec<String> {
+        [
+            "467..114..",
+            "...*......",
+            "..35..633.",
+            "......#...",
+            "617*......",
+            ".....+.58.",
+            "..592.....",
+            "......755.",
+            "...$.*....",
+            ".664.598..",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect()
+    }
+
+    #[test]
+    fn parse_numbers_and_symbols() {
+        let schematic = Schematic::parse(&example());
+
+        assert_eq!(schematic.get_numbers().len(), 10);
+        assert_eq!(schematic.get_symbols().len(), 6);
+        assert_eq!(schematic.get_numbers()[2].value, 35);
+        assert_eq!(schematic.get_numbers()[2].start_col, 2);
+        assert_eq!(schematic.get_numbers()[2].end_col, 3);
+    }
+
+    #[test]
+    fn adjacency_both_ways() {
+        let schematic = Schematic::parse(&example());
+
+        // 114 and 58 are the only numbers without a neighbouring symbol
+        let part_numbers: Vec<u32> = schematic.part_numbers().map(|n| n.value).collect();
+        assert_eq!(part_numbers, vec![467, 35, 633, 617, 592, 755, 664, 598]);
+        assert_eq!(part_numbers.iter().sum::<u32>(), 4361);
+
+        let first_gear = schematic.symbols_adjacent_to(0)[0];
+        assert_eq!(schematic.get_symbols()[first_gear].kind, '*');
+        assert_eq!(schematic.numbers_adjacent_to(first_gear), &[0, 2]);
+
+        let gears: Vec<usize> = schematic.symbols_with_neighbours('*', 2).collect();
+        assert_eq!(gears.len(), 2);
+        assert_eq!(schematic.symbols_with_neighbours('*', 1).count(), 1);
+        assert_eq!(schematic.symbols_with_neighbours('#', 3).count(), 0);
+    }
+
+    #[test]
+    fn numbers_at_grid_edges() {
+        let lines: Vec<String> = ["12*", "...", "#.7"]
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+        let schematic = Schematic::parse(&lines);
+
+        assert_eq!(schematic.part_numbers().count(), 1);
+        assert_eq!(schematic.numbers_adjacent_to(1), &[] as &[usize]);
+    }
+}
@@ -4,6 +4,7 @@ use crate::scratch_card::{ScratchCard, ScratchCardBoard};
 use common::filereader;
 use regex::Regex;
 use std::collections::HashSet;
+use std::env;
 
 fn parse_line(line: &String) -> ScratchCard {
     let scratch_card_regex = Regex::new(r"^Card +\d+: +((\d+ +)+)\| +((\d+ *)+)").unwrap();
@@ -37,19 +38,96 @@ fn solve(lines: Vec<String>) -> u32 {
         .sum()
 }
 
-fn solve2(lines: Vec<String>) -> u32 {
+fn parse_board(lines: Vec<String>) -> ScratchCardBoard {
     let scratch_card_board =
         ScratchCardBoard::new(lines.iter().map(|line| parse_line(line)).collect());
 
-    scratch_card_board.compute_winners()
+    if let Err(e) = scratch_card_board.validate() {
+        panic!("Invalid scratch card table: {:?}", e);
+    }
+
+    scratch_card_board
+}
+
+fn solve2(lines: Vec<String>) -> u64 {
+    parse_board(lines).compute_winners()
+}
+
+// One line per card, e.g. `Card 1: 4 matches, 1 copies, spawned cards 2-5`
+fn report(lines: Vec<String>) -> Vec<String> {
+    let cascade = parse_board(lines).cascade();
+
+    cascade
+        .get_cards()
+        .iter()
+        .enumerate()
+        .map(|(i, card)| {
+            let spawned = if card.spawned.is_empty() {
+                String::from("spawned nothing")
+            } else {
+                format!(
+                    "spawned cards {}-{}",
+                    card.spawned.start + 1,
+                    card.spawned.end
+                )
+            };
+
+            format!(
+                "Card {}: {} matches, {} copies, {}",
+                i + 1,
+                card.matches,
+                card.copies,
+                spawned
+            )
+        })
+        .collect()
 }
 
 fn main() {
     match filereader::read_file("./day4/resources/input.txt") {
         Ok(lines) => {
+            // `--report` prints the per-card breakdown ahead of the total
+            if env::args().any(|arg| arg == "--report") {
+                for line in report(lines.clone()) {
+                    println!("{}", line);
+                }
+            }
             let result = solve2(lines);
             println!("{:?}", result)
         }
         Err(e) => panic!("{}", e),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{report, solve, solve2};
+
+    fn example() -> Vec<String> {
+        vec![
+            String::from("Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53"),
+            String::from("Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19"),
+            String::from("Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1"),
+            String::from("Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83"),
+            String::from("Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36"),
+            String::from("Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"),
+        ]
+    }
+
+    #[test]
+    fn example_cards() {
+        assert_eq!(solve(example()), 13);
+        assert_eq!(solve2(example()), 30);
+        assert_eq!(
+            report(example()),
+            vec![
+                "Card 1: 4 matches, 1 copies, spawned cards 2-5",
+                "Card 2: 2 matches, 2 copies, spawned cards 3-4",
+                "Card 3: 2 matches, 4 copies, spawned cards 4-5",
+                "Card 4: 1 matches, 8 copies, spawned cards 5-5",
+                "Card 5: 0 matches, 14 copies, spawned nothing",
+                "Card 6: 0 matches, 1 copies, spawned nothing",
+            ]
+        );
+    }
+}
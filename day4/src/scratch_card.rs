@@ -1,4 +1,5 @@
 use std::collections::HashSet;
+use std::ops::Range;
 
 pub struct ScratchCard {
     winners: HashSet<u32>,
@@ -27,11 +28,39 @@ impl ScratchCard {
         0
     }
 
-    fn get_num_winners(&self) -> u32 {
+    pub fn get_num_winners(&self) -> u32 {
         self.num_winners
     }
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub enum ScratchCardError {
+    // A card (by index) whose matches would hand out copies of cards past the last one
+    SpawnsPastEnd { card: usize, matches: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardBreakdown {
+    pub matches: u32,
+    pub copies: u64,
+    // Indices of the cards that each copy of this one wins a copy of
+    pub spawned: Range<usize>,
+}
+
+pub struct Cascade {
+    cards: Vec<CardBreakdown>,
+}
+
+impl Cascade {
+    pub fn get_cards(&self) -> &[CardBreakdown] {
+        &self.cards
+    }
+
+    pub fn get_total(&self) -> u64 {
+        self.cards.iter().map(|card| card.copies).sum()
+    }
+}
+
 pub struct ScratchCardBoard {
     cards: Vec<ScratchCard>,
 }
@@ -41,22 +70,102 @@ impl ScratchCardBoard {
         ScratchCardBoard { cards }
     }
 
-    // Compute the total number of scratchcards won (part 2)
-    // https://adventofcode.com/2023/day/4#part2
-    pub fn compute_winners(&self) -> u32 {
-        let mut card_counts = vec![1; self.cards.len()];
-
+    pub fn validate(&self) -> Result<(), ScratchCardError> {
         for (i, card) in self.cards.iter().enumerate() {
-            println!("Card num: {}; Card counts: {}", i + 1, card_counts[i]);
-            for _ in 0..card_counts[i] {
-                for j in 1..=card.get_num_winners() as usize {
-                    if i + j < card_counts.len() {
-                        card_counts[i + j] += 1;
-                    }
-                }
+            if i + card.get_num_winners() as usize >= self.cards.len() {
+                return Err(ScratchCardError::SpawnsPastEnd {
+                    card: i,
+                    matches: card.get_num_winners(),
+                });
             }
         }
 
-        card_counts.iter().sum()
+        Ok(())
+    }
+
+    // Every card's copies are final by the time we reach it, since only earlier cards hand
+    // out copies. Rather than bumping each spawned card one by one, the copies are added to
+    // a running total at the start of the spawned range and taken off again past its end.
+    pub fn cascade(&self) -> Cascade {
+        let num_cards = self.cards.len();
+        let mut pending = vec![0_i64; num_cards + 1];
+        let mut running = 0_i64;
+        let mut cards = Vec::with_capacity(num_cards);
+
+        for (i, card) in self.cards.iter().enumerate() {
+            running += pending[i];
+
+            let copies = (1 + running) as u64;
+            let matches = card.get_num_winners();
+            let spawned = (i + 1).min(num_cards)..(i + 1 + matches as usize).min(num_cards);
+
+            pending[spawned.start] += copies as i64;
+            pending[spawned.end] -= copies as i64;
+
+            cards.push(CardBreakdown {
+                matches,
+                copies,
+                spawned,
+            });
+        }
+
+        Cascade { cards }
+    }
+
+    // Compute the total number of scratchcards won (part 2)
+    // https://adventofcode.com/2023/day/4#part2
+    pub fn compute_winners(&self) -> u64 {
+        self.cascade().get_total()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scratch_card::{ScratchCard, ScratchCardBoard, ScratchCardError};
+    use std::collections::HashSet;
+
+    // A card with exactly `matches` winning numbers drawn
+    fn card(matches: u32) -> ScratchCard {
+        let winners: HashSet<u32> = (0..5).collect();
+        let drawn: HashSet<u32> = (0..matches).chain(100..105).collect();
+
+        ScratchCard::new(winners, drawn)
+    }
+
+    #[test]
+    fn cascade_breakdown() {
+        let board =
+            ScratchCardBoard::new(vec![card(4), card(2), card(2), card(1), card(0), card(0)]);
+        let cascade = board.cascade();
+
+        let copies: Vec<u64> = cascade.get_cards().iter().map(|c| c.copies).collect();
+        let matches: Vec<u32> = cascade.get_cards().iter().map(|c| c.matches).collect();
+
+        assert_eq!(copies, vec![1, 2, 4, 8, 14, 1]);
+        assert_eq!(matches, vec![4, 2, 2, 1, 0, 0]);
+        assert_eq!(cascade.get_cards()[0].spawned, 1..5);
+        assert_eq!(cascade.get_cards()[4].spawned, 5..5);
+        assert_eq!(cascade.get_total(), 30);
+        assert_eq!(board.compute_winners(), 30);
+        assert_eq!(board.validate(), Ok(()));
+    }
+
+    #[test]
+    fn cards_spawning_past_the_end() {
+        let board = ScratchCardBoard::new(vec![card(1), card(3), card(1)]);
+
+        assert_eq!(
+            board.validate(),
+            Err(ScratchCardError::SpawnsPastEnd {
+                card: 1,
+                matches: 3
+            })
+        );
+
+        // The cascade still clips at the last card rather than failing
+        let cascade = board.cascade();
+        assert_eq!(cascade.get_cards()[1].spawned, 2..3);
+        assert_eq!(cascade.get_cards()[2].spawned, 3..3);
+        assert_eq!(cascade.get_total(), 1 + 2 + 3);
     }
 }
@@ -1,63 +1,42 @@
-use common::filereader;
-
-fn get_distance_for_time_held(time_held: u64, time_to_race: u64) -> u64 {
-    let remaining_time = time_to_race - time_held;
-    let mut distance = time_held * remaining_time;
-
-    distance
-}
+mod race;
 
-fn get_ways_for_race(time: u64, distance: u64) -> u64 {
-    // Iterate through the number of seconds we could hold the button down
-    let winning_distances: Vec<u64> = (0..time)
-        .map(|n| get_distance_for_time_held(n, time))
-        .filter(|&d| d > distance)
-        .collect();
-
-    winning_distances.len() as u64
-}
+use crate::race::Race;
+use common::filereader;
 
-fn get_races_for_lines(lines: &Vec<String>) -> Vec<(u64, u64)> {
+fn get_races_for_lines(lines: &Vec<String>) -> Vec<Race> {
     let times_line: Vec<&str> = lines[0].split_whitespace().collect();
     let distances_line: Vec<&str> = lines[1].split_whitespace().collect();
 
-    let zipped: Vec<(u64, u64)> = times_line[1..]
+    times_line[1..]
         .iter()
         .zip(distances_line[1..].iter())
         .map(|(&time, &distance)| {
-            (
+            Race::new(
                 time.parse::<u64>().unwrap(),
                 distance.parse::<u64>().unwrap(),
             )
         })
-        .collect();
-
-    zipped
+        .collect()
 }
 
-fn get_race_for_lines(lines: &Vec<String>) -> (u64, u64) {
+fn get_race_for_lines(lines: &Vec<String>) -> Race {
     let times_line: Vec<&str> = lines[0].split_whitespace().collect();
     let distances_line: Vec<&str> = lines[1].split_whitespace().collect();
 
     let time = times_line[1..].join("").parse::<u64>().unwrap();
     let distance = distances_line[1..].join("").parse::<u64>().unwrap();
 
-    (time, distance)
+    Race::new(time, distance)
 }
 
 fn solve(lines: Vec<String>) -> u64 {
     let races = get_races_for_lines(&lines);
 
-    races
-        .iter()
-        .map(|&(time, distance)| get_ways_for_race(time, distance))
-        .product()
+    races.iter().map(|race| race.count_ways()).product()
 }
 
 fn solve2(lines: Vec<String>) -> u64 {
-    let (time, distance) = get_race_for_lines(&lines);
-
-    get_ways_for_race(time, distance)
+    get_race_for_lines(&lines).count_ways()
 }
 
 fn main() {
@@ -69,3 +48,19 @@ fn main() {
         Err(e) => panic!("{}", e),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{solve, solve2};
+
+    #[test]
+    fn example_races() {
+        let lines = vec![
+            String::from("Time:      7  15   30"),
+            String::from("Distance:  9  40  200"),
+        ];
+
+        assert_eq!(solve(lines.clone()), 288);
+        assert_eq!(solve2(lines), 71503);
+    }
+}
@@ -0,0 +1,119 @@
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Race {
+    time: u64,
+    distance: u64,
+}
+
+impl Race {
+    pub fn new(time: u64, distance: u64) -> Self {
+        Race { time, distance }
+    }
+
+    fn beats_record(&self, held: u128) -> bool {
+        held * (self.time as u128 - held) > self.distance as u128
+    }
+
+    // Hold times h with h * (time - h) > distance, i.e. strictly between the roots of
+    // h^2 - time * h + distance. Everything is done in u128 so `time^2` can't overflow, and
+    // the integer square root only gets us within one of the boundary, so it is nudged into
+    // place against the original inequality.
+    pub fn winning_hold_times(&self) -> Option<RangeInclusive<u64>> {
+        let time = self.time as u128;
+        let discriminant = (time * time).checked_sub(4 * self.distance as u128)?;
+
+        let mut low = (time - isqrt(discriminant)) / 2;
+
+        while low <= time / 2 && !self.beats_record(low) {
+            low += 1;
+        }
+
+        while low > 0 && self.beats_record(low - 1) {
+            low -= 1;
+        }
+
+        if low > time / 2 {
+            return None;
+        }
+
+        // h and time - h travel the same distance, so the interval is symmetric
+        Some(low as u64..=(time - low) as u64)
+    }
+
+    pub fn count_ways(&self) -> u64 {
+        self.winning_hold_times()
+            .map_or(0, |range| range.end() - range.start() + 1)
+    }
+}
+
+// Largest r with r * r <= n
+fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+
+    let mut root = (n as f64).sqrt() as u128;
+
+    // The float estimate can land either side of the true root, and squaring it can
+    // overflow when n is near u128::MAX
+    while root.checked_mul(root).is_none_or(|square| square > n) {
+        root -= 1;
+    }
+
+    while (root + 1)
+        .checked_mul(root + 1)
+        .is_some_and(|square| square <= n)
+    {
+        root += 1;
+    }
+
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::race::{isqrt, Race};
+
+    #[test]
+    fn example_races() {
+        assert_eq!(Race::new(7, 9).winning_hold_times(), Some(2..=5));
+        assert_eq!(Race::new(15, 40).winning_hold_times(), Some(4..=11));
+        assert_eq!(Race::new(30, 200).winning_hold_times(), Some(11..=19));
+        assert_eq!(Race::new(71530, 940200).count_ways(), 71503);
+    }
+
+    #[test]
+    fn boundaries_match_brute_force() {
+        for time in 0..60 {
+            for distance in 0..time * time / 4 + 2 {
+                let race = Race::new(time, distance);
+                let winners: Vec<u64> = (0..=time).filter(|&h| h * (time - h) > distance).collect();
+
+                let expected = match (winners.first(), winners.last()) {
+                    (Some(&low), Some(&high)) => Some(low..=high),
+                    _ => None,
+                };
+
+                assert_eq!(race.winning_hold_times(), expected, "{} {}", time, distance);
+            }
+        }
+    }
+
+    #[test]
+    fn huge_races() {
+        assert_eq!(
+            Race::new(u64::MAX, 0).winning_hold_times(),
+            Some(1..=u64::MAX - 1)
+        );
+        assert_eq!(Race::new(u64::MAX, u64::MAX).count_ways(), u64::MAX - 3);
+        // The best hold time only just beats the record
+        assert_eq!(
+            Race::new(1 << 33, u64::MAX).winning_hold_times(),
+            Some(1 << 32..=1 << 32)
+        );
+        assert_eq!(Race::new(1 << 32, u64::MAX).winning_hold_times(), None);
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+        assert_eq!(isqrt(99_999_999_999_999_999_999_999), 316_227_766_016);
+    }
+}
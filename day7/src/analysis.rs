@@ -0,0 +1,275 @@
+use crate::hand::{Hand, HandType};
+use crate::ruleset::Ruleset;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Decider {
+    HandType,
+    // Index of the first card that differs between the two hands
+    Card(usize),
+    Tie,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Substitution {
+    pub index: usize,
+    pub wildcard: char,
+    pub became: char,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct HandSummary {
+    pub cards: String,
+    pub hand_type: HandType,
+    pub substitutions: Vec<Substitution>,
+}
+
+impl HandSummary {
+    fn new<R: Ruleset>(hand: &Hand<R>) -> Self {
+        let resolved = hand.get_resolved_cards();
+
+        HandSummary {
+            cards: hand.get_cards().iter().collect(),
+            hand_type: hand.get_hand_type(),
+            substitutions: hand
+                .get_cards()
+                .iter()
+                .enumerate()
+                .filter(|&(_, &card)| R::is_wildcard(card))
+                .map(|(index, &wildcard)| Substitution {
+                    index,
+                    wildcard,
+                    became: resolved[index],
+                })
+                .collect(),
+        }
+    }
+
+    fn describe(&self) -> String {
+        let mut details = vec![format!("{:?}", self.hand_type)];
+
+        details.extend(
+            self.substitutions
+                .iter()
+                .map(|sub| format!("{}@{}->{}", sub.wildcard, sub.index, sub.became)),
+        );
+
+        format!("{} ({})", self.cards, details.join(", "))
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ComparisonExplanation {
+    pub ordering: Ordering,
+    pub left: HandSummary,
+    pub right: HandSummary,
+    pub decided_by: Decider,
+}
+
+impl ComparisonExplanation {
+    // e.g. `KTJJT (FourOfAKind, J@2->T, J@3->T) beats KK677 (TwoPair) on hand type`
+    pub fn describe(&self) -> String {
+        let verdict = match self.ordering {
+            Ordering::Greater => "beats",
+            Ordering::Less => "loses to",
+            Ordering::Equal => "ties with",
+        };
+
+        let reason = match self.decided_by {
+            Decider::HandType => String::from("on hand type"),
+            Decider::Card(index) => format!(
+                "on card {} ({} vs {})",
+                index,
+                self.left.cards.chars().nth(index).unwrap(),
+                self.right.cards.chars().nth(index).unwrap()
+            ),
+            Decider::Tie => String::from("with identical cards"),
+        };
+
+        format!(
+            "{} {} {} {}",
+            self.left.describe(),
+            verdict,
+            self.right.describe(),
+            reason
+        )
+    }
+}
+
+pub fn explain<R: Ruleset>(left: &Hand<R>, right: &Hand<R>) -> ComparisonExplanation {
+    let decided_by = if left.get_hand_type() != right.get_hand_type() {
+        Decider::HandType
+    } else {
+        left.get_cards()
+            .iter()
+            .zip(right.get_cards().iter())
+            .position(|(a, b)| a != b)
+            .map_or(Decider::Tie, Decider::Card)
+    };
+
+    ComparisonExplanation {
+        ordering: left.cmp(right),
+        left: HandSummary::new(left),
+        right: HandSummary::new(right),
+        decided_by,
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RankedHand {
+    pub rank: u64,
+    pub summary: HandSummary,
+    pub bet: u64,
+    pub winnings: u64,
+}
+
+pub struct WinningsReport {
+    pub hands: Vec<RankedHand>,
+    pub type_counts: BTreeMap<HandType, usize>,
+}
+
+impl WinningsReport {
+    pub fn new<R: Ruleset>(mut hands: Vec<Hand<R>>) -> Self {
+        hands.sort();
+
+        let mut type_counts = BTreeMap::new();
+
+        let hands = hands
+            .iter()
+            .enumerate()
+            .map(|(index, hand)| {
+                *type_counts.entry(hand.get_hand_type()).or_insert(0) += 1;
+
+                RankedHand {
+                    rank: index as u64 + 1,
+                    summary: HandSummary::new(hand),
+                    bet: hand.bet(),
+                    winnings: (index as u64 + 1) * hand.bet(),
+                }
+            })
+            .collect();
+
+        WinningsReport { hands, type_counts }
+    }
+
+    pub fn get_total(&self) -> u64 {
+        self.hands.iter().map(|hand| hand.winnings).sum()
+    }
+
+    pub fn to_lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .hands
+            .iter()
+            .map(|hand| {
+                format!(
+                    "#{} {} bet {} wins {}",
+                    hand.rank,
+                    hand.summary.describe(),
+                    hand.bet,
+                    hand.winnings
+                )
+            })
+            .collect();
+
+        lines.extend(
+            self.type_counts
+                .iter()
+                .map(|(hand_type, count)| format!("{:?}: {}", hand_type, count)),
+        );
+        lines.push(format!("Total winnings: {}", self.get_total()));
+
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{explain, Decider, Substitution, WinningsReport};
+    use crate::hand::{Hand, HandType};
+    use crate::ruleset::{Jokers, Standard};
+    use std::cmp::Ordering;
+
+    #[test]
+    fn explains_type_and_card_decisions() {
+        let by_type = explain(
+            &Hand::<Jokers>::new("KTJJT", 220),
+            &Hand::<Jokers>::new("KK677", 28),
+        );
+
+        assert_eq!(by_type.ordering, Ordering::Greater);
+        assert_eq!(by_type.decided_by, Decider::HandType);
+        assert_eq!(by_type.left.hand_type, HandType::FourOfAKind);
+        assert_eq!(
+            by_type.left.substitutions,
+            vec![
+                Substitution {
+                    index: 2,
+                    wildcard: 'J',
+                    became: 'T'
+                },
+                Substitution {
+                    index: 3,
+                    wildcard: 'J',
+                    became: 'T'
+                }
+            ]
+        );
+        assert_eq!(
+            by_type.describe(),
+            "KTJJT (FourOfAKind, J@2->T, J@3->T) beats KK677 (TwoPair) on hand type"
+        );
+
+        let by_card = explain(
+            &Hand::<Jokers>::new("T55J5", 684),
+            &Hand::<Jokers>::new("QQQJA", 483),
+        );
+
+        assert_eq!(by_card.decided_by, Decider::Card(0));
+        assert_eq!(
+            by_card.describe(),
+            "T55J5 (FourOfAKind, J@3->5) loses to QQQJA (FourOfAKind, J@3->Q) on card 0 (T vs Q)"
+        );
+
+        let tie = explain(
+            &Hand::<Standard>::new("AAAAA", 1),
+            &Hand::<Standard>::new("AAAAA", 2),
+        );
+
+        assert_eq!(tie.ordering, Ordering::Equal);
+        assert_eq!(tie.decided_by, Decider::Tie);
+    }
+
+    #[test]
+    fn winnings_report() {
+        let hands: Vec<Hand<Jokers>> = [
+            ("32T3K", 765),
+            ("T55J5", 684),
+            ("KK677", 28),
+            ("KTJJT", 220),
+            ("QQQJA", 483),
+        ]
+        .iter()
+        .map(|&(cards, bet)| Hand::new(cards, bet))
+        .collect();
+
+        let report = WinningsReport::new(hands);
+
+        assert_eq!(report.get_total(), 5905);
+        assert_eq!(report.type_counts[&HandType::FourOfAKind], 3);
+        assert_eq!(
+            report.to_lines(),
+            vec![
+                "#1 32T3K (OnePair) bet 765 wins 765",
+                "#2 KK677 (TwoPair) bet 28 wins 56",
+                "#3 T55J5 (FourOfAKind, J@3->5) bet 684 wins 2052",
+                "#4 QQQJA (FourOfAKind, J@3->Q) bet 483 wins 1932",
+                "#5 KTJJT (FourOfAKind, J@2->T, J@3->T) bet 220 wins 1100",
+                "OnePair: 1",
+                "TwoPair: 1",
+                "FourOfAKind: 3",
+                "Total winnings: 5905",
+            ]
+        );
+    }
+}
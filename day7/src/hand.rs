@@ -1,163 +1,197 @@
+use crate::ruleset::Ruleset;
 use std::cmp::Ordering;
 use std::collections::HashMap;
-
-#[derive(Hash)]
-enum Card {
-    Ace,
-    King,
-    Queen,
-    Jack,
-    Ten,
-    Nine,
-    Eight,
-    Seven,
-    Six,
-    Five,
-    Four,
-    Three,
-    Two,
+use std::fmt;
+use std::marker::PhantomData;
+
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub enum HandType {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
 }
 
-impl Card {
-    fn value(&self) -> u8 {
-        match self {
-            Card::Two => 2,
-            Card::Three => 3,
-            Card::Four => 4,
-            Card::Five => 5,
-            Card::Six => 6,
-            Card::Seven => 7,
-            Card::Eight => 8,
-            Card::Nine => 9,
-            Card::Ten => 10,
-            Card::Jack => 11,
-            Card::Queen => 12,
-            Card::King => 13,
-            Card::Ace => 14,
-        }
-    }
-}
+impl HandType {
+    pub fn from_cards(cards: &[char; 5]) -> Self {
+        let mut card_counts: HashMap<char, u8> = HashMap::new();
 
-impl PartialOrd for Card {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for Card {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.value().cmp(&other.value())
-    }
-}
+        for &card in cards {
+            *card_counts.entry(card).or_insert(0) += 1;
+        }
 
-impl PartialEq for Card {
-    fn eq(&self, other: &Self) -> bool {
-        self.value() == other.value()
+        match card_counts.values().max().unwrap() {
+            5 => HandType::FiveOfAKind,
+            4 => HandType::FourOfAKind,
+            3 if card_counts.len() == 2 => HandType::FullHouse,
+            3 => HandType::ThreeOfAKind,
+            2 if card_counts.len() == 3 => HandType::TwoPair,
+            2 => HandType::OnePair,
+            1 => HandType::HighCard,
+            _ => panic!("Invalid card counts"),
+        }
     }
 }
 
-impl Eq for Card {}
-
-#[derive(Eq, PartialEq)]
-pub struct Hand {
-    cards: [Card; 5],
+pub struct Hand<R: Ruleset> {
+    cards: [char; 5],
+    strengths: [u8; 5],
     bet: u64,
-    rank: u8,
-}
-
-fn compute_rank(cards: &[Card; 5]) -> u8 {
-    let mut card_counts = HashMap::new();
-
-    for item in cards {
-        *card_counts.entry(item).or_insert(0) += 1;
-    }
-
-    match card_counts.values().max().unwrap() {
-        5 => return 7,
-        4 => return 6,
-        3 => {
-            return if card_counts.len() == 2 {
-                // Full house
-                5
-            } else {
-                // Three of a kind
-                4
-            };
-        }
-        2 => {
-            return if card_counts.len() == 3 {
-                // Two pairs
-                3
-            } else {
-                // One pair
-                2
-            };
-        }
-        1 => return 1,
-        _ => panic!("Invalid card counts"),
-    }
+    hand_type: HandType,
+    ruleset: PhantomData<R>,
 }
 
-impl Hand {
+impl<R: Ruleset> Hand<R> {
     pub fn new(cards: &str, bet: u64) -> Self {
-        if cards.len() != 5 {
+        let labels: Vec<char> = cards.chars().collect();
+
+        if labels.len() != 5 {
             panic!("Each hand should have exactly 5 cards. Got {}", cards);
         }
 
-        let mut cards_iter = cards.chars().filter_map(|c| match c {
-            '2' => Some(Card::Two),
-            '3' => Some(Card::Three),
-            '4' => Some(Card::Four),
-            '5' => Some(Card::Five),
-            '6' => Some(Card::Six),
-            '7' => Some(Card::Seven),
-            '8' => Some(Card::Eight),
-            '9' => Some(Card::Nine),
-            'T' => Some(Card::Ten),
-            'J' => Some(Card::Jack),
-            'Q' => Some(Card::Queen),
-            'K' => Some(Card::King),
-            'A' => Some(Card::Ace),
-            _ => None,
+        let cards = [labels[0], labels[1], labels[2], labels[3], labels[4]];
+        let strengths = cards.map(|label| match R::card_strength(label) {
+            Some(strength) => strength,
+            None => panic!(
+                "Unknown card {} in hand {}",
+                label,
+                cards.iter().collect::<String>()
+            ),
         });
 
-        let cards = [
-            cards_iter.next().unwrap(),
-            cards_iter.next().unwrap(),
-            cards_iter.next().unwrap(),
-            cards_iter.next().unwrap(),
-            cards_iter.next().unwrap(),
-        ];
-
         Hand {
-            bet,
-            rank: compute_rank(&cards),
             cards,
+            strengths,
+            bet,
+            hand_type: R::hand_type(&cards),
+            ruleset: PhantomData,
         }
     }
 
     pub fn bet(&self) -> u64 {
         self.bet
     }
+
+    pub fn get_cards(&self) -> &[char; 5] {
+        &self.cards
+    }
+
+    pub fn get_hand_type(&self) -> HandType {
+        self.hand_type
+    }
+
+    // The cards with every wildcard swapped for what it counted as when typing the hand
+    pub fn get_resolved_cards(&self) -> [char; 5] {
+        R::resolve_wildcards(&self.cards)
+    }
+}
+
+impl<R: Ruleset> fmt::Debug for Hand<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:?} {}",
+            self.cards.iter().collect::<String>(),
+            self.hand_type,
+            self.bet
+        )
+    }
 }
 
-impl Ord for Hand {
+impl<R: Ruleset> Ord for Hand<R> {
     fn cmp(&self, other: &Self) -> Ordering {
-        match self.rank.cmp(&other.rank) {
-            Ordering::Equal => self
-                .cards
-                .iter()
-                .zip(other.cards.iter())
-                .map(|(a, b)| a.cmp(b))
-                .find(|&order| order != Ordering::Equal)
-                .unwrap_or(Ordering::Equal),
-            order => order,
-        }
+        self.hand_type
+            .cmp(&other.hand_type)
+            .then_with(|| self.strengths.cmp(&other.strengths))
     }
 }
 
-impl PartialOrd for Hand {
+impl<R: Ruleset> PartialOrd for Hand<R> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
+
+impl<R: Ruleset> PartialEq for Hand<R> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<R: Ruleset> Eq for Hand<R> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::hand::{Hand, HandType};
+    use crate::ruleset::{Jokers, Ruleset, Standard};
+
+    // Twos and jacks are both wild, over a shorter alphabet with no tens or nines
+    struct DoubleWild;
+
+    impl Ruleset for DoubleWild {
+        const ALPHABET: &'static str = "2J345678QKA";
+        const WILDCARDS: &'static str = "2J";
+    }
+
+    #[test]
+    fn hand_types() {
+        for (cards, standard, jokers) in [
+            ("32T3K", HandType::OnePair, HandType::OnePair),
+            ("KK677", HandType::TwoPair, HandType::TwoPair),
+            ("KTJJT", HandType::TwoPair, HandType::FourOfAKind),
+            ("T55J5", HandType::ThreeOfAKind, HandType::FourOfAKind),
+            ("QQQJA", HandType::ThreeOfAKind, HandType::FourOfAKind),
+            ("JJJJJ", HandType::FiveOfAKind, HandType::FiveOfAKind),
+            ("2345J", HandType::HighCard, HandType::OnePair),
+            ("23J32", HandType::TwoPair, HandType::FullHouse),
+        ] {
+            assert_eq!(
+                Hand::<Standard>::new(cards, 0).get_hand_type(),
+                standard,
+                "{}",
+                cards
+            );
+            assert_eq!(
+                Hand::<Jokers>::new(cards, 0).get_hand_type(),
+                jokers,
+                "{}",
+                cards
+            );
+        }
+    }
+
+    #[test]
+    fn ordering_depends_on_ruleset() {
+        assert!(Hand::<Standard>::new("KK677", 0) > Hand::<Standard>::new("KTJJT", 0));
+        assert!(Hand::<Jokers>::new("KK677", 0) < Hand::<Jokers>::new("KTJJT", 0));
+        // Same type, so the first card decides: J beats T normally but loses as a joker
+        assert!(Hand::<Standard>::new("JKKK2", 0) > Hand::<Standard>::new("TKKK2", 0));
+        assert!(Hand::<Jokers>::new("JKKK2", 0) < Hand::<Jokers>::new("QQQQ2", 0));
+        assert_eq!(
+            Hand::<Jokers>::new("JJJJJ", 0),
+            Hand::<Jokers>::new("JJJJJ", 5)
+        );
+    }
+
+    #[test]
+    fn custom_ruleset() {
+        assert_eq!(
+            Hand::<DoubleWild>::new("2J3KK", 0).get_hand_type(),
+            HandType::FourOfAKind
+        );
+        assert_eq!(
+            DoubleWild::resolve_wildcards(&['2', 'J', '2', 'J', '2']),
+            ['A'; 5]
+        );
+        assert!(Hand::<DoubleWild>::new("J3456", 0) > Hand::<DoubleWild>::new("23456", 0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn rejects_unknown_cards() {
+        Hand::<DoubleWild>::new("T3456", 0);
+    }
+}
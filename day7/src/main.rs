@@ -1,11 +1,14 @@
+mod analysis;
 mod hand;
-mod jhand;
+mod ruleset;
 
+use crate::analysis::{explain, WinningsReport};
 use crate::hand::Hand;
-use crate::jhand::JHand;
+use crate::ruleset::{Jokers, Ruleset, Standard};
 use common::filereader;
+use std::env;
 
-fn make_hand_from_line(line: &String) -> Hand {
+fn make_hand_from_line<R: Ruleset>(line: &String) -> Hand<R> {
     let split_line: Vec<&str> = line.split_whitespace().collect();
 
     let cards = &split_line[0];
@@ -14,46 +17,74 @@ fn make_hand_from_line(line: &String) -> Hand {
     Hand::new(cards, bet)
 }
 
-fn make_jhand_from_line(line: &String) -> JHand {
-    let split_line: Vec<&str> = line.split_whitespace().collect();
-
-    let cards = &split_line[0];
-    let bet = split_line[1].parse::<u64>().unwrap();
-
-    JHand::new(cards, bet)
+fn winnings_report<R: Ruleset>(lines: Vec<String>) -> WinningsReport {
+    WinningsReport::new(
+        lines
+            .iter()
+            .map(|line| make_hand_from_line::<R>(line))
+            .collect(),
+    )
 }
 
 fn solve(lines: Vec<String>) -> u64 {
-    let mut hands: Vec<Hand> = lines.iter().map(|line| make_hand_from_line(line)).collect();
-
-    hands.sort();
-
-    hands.iter().enumerate().fold(0, |acc, (index, hand)| {
-        acc + (index as u64 + 1u64) * hand.bet()
-    })
+    winnings_report::<Standard>(lines).get_total()
 }
 
 fn solve2(lines: Vec<String>) -> u64 {
-    let mut hands: Vec<JHand> = lines
-        .iter()
-        .map(|line| make_jhand_from_line(line))
-        .collect();
-
-    hands.sort();
+    winnings_report::<Jokers>(lines).get_total()
+}
 
-    println!("Sorted results: {:?}", hands);
+// `--explain HAND HAND` compares two hands under the joker rules
+fn hands_to_compare() -> Option<(String, String)> {
+    let args: Vec<String> = env::args().collect();
+    let flag = args.iter().position(|arg| arg == "--explain")?;
 
-    hands.iter().enumerate().fold(0, |acc, (index, hand)| {
-        acc + (index as u64 + 1u64) * hand.bet()
-    })
+    match (args.get(flag + 1), args.get(flag + 2)) {
+        (Some(left), Some(right)) => Some((left.clone(), right.clone())),
+        _ => panic!("--explain needs two hands"),
+    }
 }
 
 fn main() {
     match filereader::read_file("./day7/resources/input.txt") {
         Ok(lines) => {
+            if let Some((left, right)) = hands_to_compare() {
+                let explanation = explain(
+                    &Hand::<Jokers>::new(&left, 0),
+                    &Hand::<Jokers>::new(&right, 0),
+                );
+                println!("{}", explanation.describe());
+            }
+            // `--report` prints every hand's rank and winnings ahead of the total
+            if env::args().any(|arg| arg == "--report") {
+                for line in winnings_report::<Jokers>(lines.clone()).to_lines() {
+                    println!("{}", line);
+                }
+            }
             let result = solve2(lines);
             println!("{:?}", result);
         }
         Err(e) => panic!("{}", e),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{solve, solve2};
+
+    fn example() -> Vec<String> {
+        vec![
+            String::from("32T3K 765"),
+            String::from("T55J5 684"),
+            String::from("KK677 28"),
+            String::from("KTJJT 220"),
+            String::from("QQQJA 483"),
+        ]
+    }
+
+    #[test]
+    fn example_winnings() {
+        assert_eq!(solve(example()), 6440);
+        assert_eq!(solve2(example()), 5905);
+    }
+}
@@ -0,0 +1,71 @@
+use crate::hand::HandType;
+
+// A variant of Camel Cards. Card labels are ranked by their position in `ALPHABET`, weakest
+// first, and any label in `WILDCARDS` stands in for whatever makes the strongest hand type.
+pub trait Ruleset {
+    const ALPHABET: &'static str;
+    const WILDCARDS: &'static str = "";
+
+    fn card_strength(label: char) -> Option<u8> {
+        Self::ALPHABET
+            .chars()
+            .position(|c| c == label)
+            .map(|idx| idx as u8)
+    }
+
+    fn is_wildcard(label: char) -> bool {
+        Self::WILDCARDS.contains(label)
+    }
+
+    // Every wildcard joins the largest group of the other cards (the stronger label on a
+    // tie), which always gives the best type. A hand of nothing but wildcards becomes the
+    // strongest ordinary card.
+    fn resolve_wildcards(cards: &[char; 5]) -> [char; 5] {
+        let mut counts: Vec<(usize, u8, char)> = Vec::new();
+
+        for &label in cards.iter().filter(|&&label| !Self::is_wildcard(label)) {
+            match counts.iter_mut().find(|(_, _, c)| *c == label) {
+                Some((count, _, _)) => *count += 1,
+                None => counts.push((1, Self::card_strength(label).unwrap_or(0), label)),
+            }
+        }
+
+        let replacement = counts.iter().max().map(|&(_, _, label)| label).or_else(|| {
+            Self::ALPHABET
+                .chars()
+                .rev()
+                .find(|&c| !Self::is_wildcard(c))
+        });
+
+        match replacement {
+            Some(replacement) => cards.map(|label| {
+                if Self::is_wildcard(label) {
+                    replacement
+                } else {
+                    label
+                }
+            }),
+            None => *cards,
+        }
+    }
+
+    fn hand_type(cards: &[char; 5]) -> HandType {
+        HandType::from_cards(&Self::resolve_wildcards(cards))
+    }
+}
+
+#[derive(Debug)]
+pub struct Standard;
+
+impl Ruleset for Standard {
+    const ALPHABET: &'static str = "23456789TJQKA";
+}
+
+// Part 2: `J` is a joker, the weakest card on its own but wild when typing the hand
+#[derive(Debug)]
+pub struct Jokers;
+
+impl Ruleset for Jokers {
+    const ALPHABET: &'static str = "J23456789TQKA";
+    const WILDCARDS: &'static str = "J";
+}
@@ -0,0 +1,69 @@
+// Combines congruences t ≡ residue (mod modulus) into a single one. The moduli don't need
+// to be coprime; `None` means no t satisfies all of them at once, or that the combined
+// modulus doesn't fit in a u128.
+pub fn solve_congruences(congruences: &[(u128, u128)]) -> Option<(u128, u128)> {
+    congruences
+        .iter()
+        .try_fold((0_u128, 1_u128), |(residue, modulus), &(r, m)| {
+            combine(residue, modulus, r, m)
+        })
+}
+
+fn combine(r1: u128, m1: u128, r2: u128, m2: u128) -> Option<(u128, u128)> {
+    let r2 = r2.checked_rem(m2)?;
+    let (g, p, _) = extended_gcd(i128::try_from(m1).ok()?, i128::try_from(m2).ok()?);
+    let diff = i128::try_from(r2).ok()? - i128::try_from(r1).ok()?;
+
+    if diff % g != 0 {
+        return None;
+    }
+
+    // r1 + m1 * k ≡ r2 (mod m2)  =>  k ≡ (diff / g) * p (mod m2 / g)
+    let step = i128::try_from(m2).ok()? / g;
+    let k = ((diff / g) % step).checked_mul(p % step)?.rem_euclid(step);
+    let modulus = (m1 / u128::try_from(g).ok()?).checked_mul(m2)?;
+    let residue = r1.checked_add(m1.checked_mul(u128::try_from(k).ok()?)?)? % modulus;
+
+    Some((residue, modulus))
+}
+
+// (g, x, y) with a * x + b * y = g = gcd(a, b)
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a % b);
+        (g, y, x - (a / b) * y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crt::solve_congruences;
+
+    #[test]
+    fn coprime_moduli() {
+        assert_eq!(
+            solve_congruences(&[(2, 3), (3, 5), (2, 7)]),
+            Some((23, 105))
+        );
+        assert_eq!(solve_congruences(&[]), Some((0, 1)));
+    }
+
+    #[test]
+    fn shared_factors() {
+        assert_eq!(solve_congruences(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(solve_congruences(&[(0, 12), (0, 18)]), Some((0, 36)));
+        assert_eq!(solve_congruences(&[(1, 4), (2, 6)]), None);
+    }
+
+    #[test]
+    fn oversized_moduli() {
+        assert_eq!(solve_congruences(&[(0, u128::MAX)]), None);
+        assert_eq!(
+            solve_congruences(&[(0, u64::MAX as u128), (1, u64::MAX as u128 - 1), (1, 7)]),
+            None
+        );
+        assert_eq!(solve_congruences(&[(3, 0)]), None);
+    }
+}
@@ -0,0 +1,202 @@
+use crate::crt::solve_congruences;
+use crate::map_node::{Direction, MapGraph, NodeId};
+use std::collections::HashMap;
+
+// Where a ghost's walk over (node, direction index) states starts repeating. Hits are the
+// step counts that land on a `Z` node: the tail ones happen once, and each cycle offset
+// happens at tail_length + offset + k * cycle_length for every k.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CycleStructure {
+    pub start: String,
+    pub tail_length: u64,
+    pub cycle_length: u64,
+    pub tail_hits: Vec<u64>,
+    pub cycle_hits: Vec<u64>,
+}
+
+impl CycleStructure {
+    pub fn analyse(graph: &MapGraph, start: NodeId, directions: &[Direction]) -> Self {
+        let mut first_seen: HashMap<(NodeId, usize), u64> = HashMap::new();
+        let mut hits = Vec::new();
+        let mut curr_node = start;
+        let mut steps = 0_u64;
+
+        loop {
+            let dir_idx = (steps % directions.len() as u64) as usize;
+
+            if let Some(&tail_length) = first_seen.get(&(curr_node, dir_idx)) {
+                let (tail_hits, cycle_hits): (Vec<u64>, Vec<u64>) =
+                    hits.into_iter().partition(|&hit| hit < tail_length);
+
+                return CycleStructure {
+                    start: graph.get_name(start).to_string(),
+                    tail_length,
+                    cycle_length: steps - tail_length,
+                    tail_hits,
+                    cycle_hits: cycle_hits.iter().map(|hit| hit - tail_length).collect(),
+                };
+            }
+
+            first_seen.insert((curr_node, dir_idx), steps);
+
+            if steps > 0 && graph.is_end(curr_node) {
+                hits.push(steps);
+            }
+
+            curr_node = graph.next(curr_node, &directions[dir_idx]);
+            steps += 1;
+        }
+    }
+
+    pub fn hits_at(&self, steps: u64) -> bool {
+        if steps < self.tail_length {
+            self.tail_hits.contains(&steps)
+        } else {
+            self.cycle_hits
+                .contains(&((steps - self.tail_length) % self.cycle_length))
+        }
+    }
+}
+
+// The first step count at which every ghost is on a `Z` node at once, if there is one
+pub fn first_common_hit(cycles: &[CycleStructure]) -> Option<u128> {
+    // Anything before every ghost has reached its cycle has to be one of the tail hits
+    let mut best = cycles
+        .iter()
+        .flat_map(|cycle| cycle.tail_hits.iter())
+        .filter(|&&hit| cycles.iter().all(|cycle| cycle.hits_at(hit)))
+        .min()
+        .map(|&hit| hit as u128);
+
+    let settled = cycles
+        .iter()
+        .map(|c| c.tail_length)
+        .max()
+        .unwrap_or(0)
+        .max(1) as u128;
+
+    // Past that, each ghost contributes one congruence per cycle offset. Folding them in a
+    // ghost at a time and keeping only the distinct solutions so far means alignments that
+    // are already impossible are dropped before the next ghost multiplies them out.
+    let mut partials: Vec<(u128, u128)> = vec![(0, 1)];
+
+    for cycle in cycles {
+        partials = partials
+            .iter()
+            .flat_map(|&partial| {
+                cycle.cycle_hits.iter().filter_map(move |&offset| {
+                    solve_congruences(&[
+                        partial,
+                        (
+                            (cycle.tail_length + offset) as u128,
+                            cycle.cycle_length as u128,
+                        ),
+                    ])
+                })
+            })
+            .collect();
+        partials.sort_unstable();
+        partials.dedup();
+    }
+
+    for (residue, modulus) in partials {
+        let candidate = if residue >= settled {
+            Some(residue)
+        } else {
+            (settled - residue)
+                .div_ceil(modulus)
+                .checked_mul(modulus)
+                .and_then(|offset| offset.checked_add(residue))
+        };
+
+        if let Some(candidate) = candidate {
+            best = Some(best.map_or(candidate, |b| b.min(candidate)));
+        }
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cycles::{first_common_hit, CycleStructure};
+    use crate::{build_graph, get_directions};
+
+    fn example() -> Vec<String> {
+        vec![
+            String::from("11A = (11B, XXX)"),
+            String::from("11B = (XXX, 11Z)"),
+            String::from("11Z = (11B, XXX)"),
+            String::from("22A = (22B, XXX)"),
+            String::from("22B = (22C, 22C)"),
+            String::from("22C = (22Z, 22Z)"),
+            String::from("22Z = (22B, 22B)"),
+            String::from("XXX = (XXX, XXX)"),
+        ]
+    }
+
+    #[test]
+    fn example_cycles() {
+        let graph = build_graph(&example());
+        let directions = get_directions("LR");
+
+        let first = CycleStructure::analyse(&graph, graph.get_id("11A").unwrap(), &directions);
+        assert_eq!(first.tail_length, 1);
+        assert_eq!(first.cycle_length, 2);
+        assert_eq!(first.tail_hits, Vec::<u64>::new());
+        assert_eq!(first.cycle_hits, vec![1]);
+
+        let second = CycleStructure::analyse(&graph, graph.get_id("22A").unwrap(), &directions);
+        assert_eq!(second.tail_length, 1);
+        assert_eq!(second.cycle_length, 6);
+        assert_eq!(second.cycle_hits, vec![2, 5]);
+
+        assert_eq!(first_common_hit(&[first, second]), Some(6));
+    }
+
+    #[test]
+    fn tails_and_impossible_alignments() {
+        let tail_only = CycleStructure {
+            start: String::from("AAA"),
+            tail_length: 10,
+            cycle_length: 3,
+            tail_hits: vec![4, 7],
+            cycle_hits: vec![],
+        };
+        let odd = CycleStructure {
+            start: String::from("BBA"),
+            tail_length: 0,
+            cycle_length: 2,
+            tail_hits: vec![],
+            cycle_hits: vec![1],
+        };
+        let even = CycleStructure {
+            start: String::from("CCA"),
+            tail_length: 0,
+            cycle_length: 4,
+            tail_hits: vec![],
+            cycle_hits: vec![2],
+        };
+
+        assert_eq!(first_common_hit(&[tail_only.clone(), odd.clone()]), Some(7));
+        assert_eq!(first_common_hit(&[odd, even.clone()]), None);
+        // Cycle offsets are only valid once the ghost has actually reached its cycle
+        assert_eq!(first_common_hit(&[tail_only, even]), None);
+    }
+
+    #[test]
+    fn many_offsets_per_ghost() {
+        // A cartesian product over these would be 10^12 sets of congruences
+        let busy: Vec<CycleStructure> = (0..12)
+            .map(|ghost| CycleStructure {
+                start: format!("{}A", ghost),
+                tail_length: 0,
+                cycle_length: 10,
+                tail_hits: vec![],
+                cycle_hits: (0..10).map(|offset| (offset + ghost) % 10).collect(),
+            })
+            .collect();
+
+        assert_eq!(first_common_hit(&busy), Some(1));
+    }
+}
@@ -1,9 +1,11 @@
+mod crt;
+mod cycles;
 mod map_node;
 
-use crate::map_node::{Direction, MapGraph, NodePath};
+use crate::map_node::{Direction, JumpTable, MapGraph, NodePath};
 use common::filereader;
 use regex::Regex;
-use std::collections::HashMap;
+use std::env;
 
 fn get_directions(line: &str) -> Vec<Direction> {
     line.chars()
@@ -17,7 +19,7 @@ fn get_directions(line: &str) -> Vec<Direction> {
 
 fn build_graph(lines: &[String]) -> MapGraph {
     let node_regex = Regex::new(r"([A-Z0-9]{3}) = \(([A-Z0-9]{3}), ([A-Z0-9]{3})\)").unwrap();
-    let mut nodes: HashMap<String, NodePath> = HashMap::new();
+    let mut nodes: Vec<NodePath> = Vec::new();
 
     // First iterate through the list and create a node for each line
     for line in lines {
@@ -26,10 +28,7 @@ fn build_graph(lines: &[String]) -> MapGraph {
             let node_left = caps.get(2).unwrap().as_str();
             let node_right = caps.get(3).unwrap().as_str();
 
-            nodes.insert(
-                node_name.to_string(),
-                NodePath::new(node_name, node_left, node_right),
-            );
+            nodes.push(NodePath::new(node_name, node_left, node_right));
         }
     }
 
@@ -47,15 +46,78 @@ fn solve2(lines: Vec<String>) -> u128 {
     let directions = get_directions(&lines[0]);
     let map_graph = build_graph(&lines[2..]);
 
-    map_graph.compute_simul_distances(&directions)
+    match map_graph.compute_simul_distances(&directions) {
+        Some(steps) => steps,
+        None => panic!("The ghosts never all reach Z nodes at the same time"),
+    }
+}
+
+// Where each ghost is after `steps` steps, as `start -> node` lines
+fn positions_after(lines: &[String], steps: u64) -> Vec<String> {
+    let directions = get_directions(&lines[0]);
+    let map_graph = build_graph(&lines[2..]);
+    let jump_table = JumpTable::new(&map_graph, &directions, steps);
+
+    map_graph
+        .start_nodes()
+        .into_iter()
+        .map(|start| {
+            let end = jump_table.position_after(start, steps);
+            format!(
+                "{} -> {}",
+                map_graph.get_name(start),
+                map_graph.get_name(end)
+            )
+        })
+        .collect()
+}
+
+// `--after N` prints where every ghost is after N steps
+fn steps_after() -> Option<u64> {
+    let args: Vec<String> = env::args().collect();
+    let flag = args.iter().position(|arg| arg == "--after")?;
+
+    match args.get(flag + 1).map(|arg| arg.parse::<u64>()) {
+        Some(Ok(steps)) => Some(steps),
+        _ => panic!("--after needs a number of steps"),
+    }
 }
 
 fn main() {
     match filereader::read_file("./day8/resources/input.txt") {
         Ok(lines) => {
+            if let Some(steps) = steps_after() {
+                for line in positions_after(&lines, steps) {
+                    println!("{}", line);
+                }
+            }
             let result = solve2(lines);
             println!("{:?}", result);
         }
         Err(e) => panic!("{}", e),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{positions_after, solve2};
+
+    #[test]
+    fn example_ghosts() {
+        let lines = vec![
+            String::from("LR"),
+            String::from(""),
+            String::from("11A = (11B, XXX)"),
+            String::from("11B = (XXX, 11Z)"),
+            String::from("11Z = (11B, XXX)"),
+            String::from("22A = (22B, XXX)"),
+            String::from("22B = (22C, 22C)"),
+            String::from("22C = (22Z, 22Z)"),
+            String::from("22Z = (22B, 22B)"),
+            String::from("XXX = (XXX, XXX)"),
+        ];
+
+        assert_eq!(positions_after(&lines, 6), vec!["11A -> 11Z", "22A -> 22Z"]);
+        assert_eq!(solve2(lines), 6);
+    }
+}
@@ -1,5 +1,7 @@
-use rand::Rng;
-use std::collections::{HashMap, HashSet};
+use crate::cycles::{first_common_hit, CycleStructure};
+use std::collections::HashMap;
+
+pub type NodeId = u32;
 
 #[derive(Eq, PartialEq)]
 pub enum Direction {
@@ -36,99 +38,244 @@ impl NodePath {
     }
 }
 
+// Node names are interned to dense ids in input order, so every step is an array lookup
 #[derive(Debug)]
 pub struct MapGraph {
-    nodes_to_paths: HashMap<String, NodePath>,
+    names: Vec<String>,
+    ids: HashMap<String, NodeId>,
+    left: Vec<NodeId>,
+    right: Vec<NodeId>,
+    is_start: Vec<bool>,
+    is_end: Vec<bool>,
 }
 
 impl MapGraph {
-    pub fn new(nodes: HashMap<String, NodePath>) -> Self {
+    pub fn new(nodes: Vec<NodePath>) -> Self {
+        let names: Vec<String> = nodes.iter().map(|node| node.name.clone()).collect();
+        let mut ids: HashMap<String, NodeId> = HashMap::with_capacity(names.len());
+
+        for (id, name) in names.iter().enumerate() {
+            if ids.insert(name.clone(), id as NodeId).is_some() {
+                panic!("Node {} is defined more than once", name);
+            }
+        }
+
+        let lookup = |name: &str| match ids.get(name) {
+            Some(&id) => id,
+            None => panic!("Path leads to unknown node {}", name),
+        };
+
+        let left = nodes.iter().map(|node| lookup(&node.left)).collect();
+        let right = nodes.iter().map(|node| lookup(&node.right)).collect();
+
         MapGraph {
-            nodes_to_paths: nodes,
+            left,
+            right,
+            is_start: nodes.iter().map(|node| node.is_start()).collect(),
+            is_end: nodes.iter().map(|node| node.is_end()).collect(),
+            names,
+            ids,
+        }
+    }
+
+    pub fn get_id(&self, name: &str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn get_name(&self, id: NodeId) -> &str {
+        &self.names[id as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_end(&self, id: NodeId) -> bool {
+        self.is_end[id as usize]
+    }
+
+    pub fn next(&self, id: NodeId, direction: &Direction) -> NodeId {
+        match direction {
+            Direction::Left => self.left[id as usize],
+            Direction::Right => self.right[id as usize],
         }
     }
 
-    pub fn compute_distance(&self, start: &str, end: &str, directions: &Vec<Direction>) -> u64 {
-        // TODO: Track visited nodes if we need to...
-        let mut curr_node = start;
+    pub fn start_nodes(&self) -> Vec<NodeId> {
+        (0..self.len() as NodeId)
+            .filter(|&id| self.is_start[id as usize])
+            .collect()
+    }
+
+    pub fn compute_distance(&self, start: &str, end: &str, directions: &[Direction]) -> u64 {
+        let mut curr_node = self.get_id(start).unwrap();
+        let end = self.get_id(end).unwrap();
         let mut iterations = 0_u64;
 
         while curr_node != end {
-            println!("Iteration: {}", iterations);
             let bounded_iter_index = (iterations % (directions.len() as u64)) as usize;
-            let next_direction = &directions[bounded_iter_index];
 
-            let node_path = self.get(curr_node);
-
-            let next_node_name = match next_direction {
-                Direction::Left => node_path.left.as_str(),
-                Direction::Right => node_path.right.as_str(),
-            };
-
-            curr_node = next_node_name;
+            curr_node = self.next(curr_node, &directions[bounded_iter_index]);
             iterations += 1;
         }
 
         iterations
     }
 
-    pub fn compute_simul_distances(&self, directions: &Vec<Direction>) -> u128 {
-        let mut curr_nodes: Vec<&NodePath> = self
-            .nodes_to_paths
-            .values()
-            .filter(|node_path| node_path.is_start)
-            .collect();
-        let mut iterations_vec: Vec<u128> = vec![0; curr_nodes.len()];
-        let mut iterations = 0_u128;
+    pub fn analyse_cycles(&self, directions: &[Direction]) -> Vec<CycleStructure> {
+        self.start_nodes()
+            .into_iter()
+            .map(|start| CycleStructure::analyse(self, start, directions))
+            .collect()
+    }
 
-        while iterations_vec.iter().any(|&it| it == 0) {
-            if rand::thread_rng().gen_range(0..1000000) < 2 {
-                println!("Iteration: {:?}", iterations_vec);
-            }
+    // `None` when the ghosts' walks never put them all on `Z` nodes at the same time
+    pub fn compute_simul_distances(&self, directions: &[Direction]) -> Option<u128> {
+        first_common_hit(&self.analyse_cycles(directions))
+    }
+}
 
-            let bounded_iter_index = (iterations % (directions.len() as u128)) as usize;
-            let next_direction = &directions[bounded_iter_index];
+// levels[k][node] is where a walk from `node` ends up after 2^k full passes over the
+// directions, so any number of passes takes one lookup per bit
+pub struct JumpTable<'a> {
+    graph: &'a MapGraph,
+    directions: &'a [Direction],
+    levels: Vec<Vec<NodeId>>,
+}
 
-            curr_nodes = curr_nodes
-                .iter()
-                .map(|node| {
-                    let node_path = self.get(node.name.as_str());
+impl<'a> JumpTable<'a> {
+    pub fn new(graph: &'a MapGraph, directions: &'a [Direction], max_steps: u64) -> Self {
+        let one_pass: Vec<NodeId> = (0..graph.len() as NodeId)
+            .map(|start| {
+                directions
+                    .iter()
+                    .fold(start, |node, direction| graph.next(node, direction))
+            })
+            .collect();
 
-                    let next_node_name = match next_direction {
-                        Direction::Left => node_path.left.as_str(),
-                        Direction::Right => node_path.right.as_str(),
-                    };
+        let max_passes = max_steps / directions.len() as u64;
+        let mut levels = vec![one_pass];
 
-                    self.get(next_node_name)
-                })
-                .collect();
+        while 1_u64
+            .checked_shl(levels.len() as u32)
+            .is_some_and(|passes| passes <= max_passes)
+        {
+            let prev = levels.last().unwrap();
+            let doubled = prev.iter().map(|&node| prev[node as usize]).collect();
+            levels.push(doubled);
+        }
 
-            curr_nodes.iter().enumerate().for_each(|(i, &&ref node)| {
-                if iterations_vec[i] == 0 && node.is_end {
-                    iterations_vec[i] = iterations + 1;
-                }
-            });
+        JumpTable {
+            graph,
+            directions,
+            levels,
+        }
+    }
 
-            iterations += 1;
+    pub fn position_after(&self, start: NodeId, steps: u64) -> NodeId {
+        let passes = steps / self.directions.len() as u64;
+        let remainder = (steps % self.directions.len() as u64) as usize;
+
+        if passes.checked_shr(self.levels.len() as u32).unwrap_or(0) != 0 {
+            panic!("Jump table was not built for {} steps", steps);
         }
 
-        println!("Finished: {:?}", iterations_vec);
+        let mut node = start;
 
-        fn gcd(a: u128, b: u128) -> u128 {
-            match b {
-                0 => a,
-                _ => gcd(b, a % b),
+        for (level, jumps) in self.levels.iter().enumerate() {
+            if passes & (1 << level) != 0 {
+                node = jumps[node as usize];
             }
         }
 
-        fn lcm(a: u128, b: u128) -> u128 {
-            (a / gcd(a, b)) * b
+        self.directions[..remainder]
+            .iter()
+            .fold(node, |node, direction| self.graph.next(node, direction))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::map_node::JumpTable;
+    use crate::{build_graph, get_directions};
+
+    fn example() -> Vec<String> {
+        vec![
+            String::from("AAA = (BBB, BBB)"),
+            String::from("BBB = (AAA, ZZZ)"),
+            String::from("ZZZ = (ZZZ, ZZZ)"),
+        ]
+    }
+
+    #[test]
+    fn interned_graph() {
+        let graph = build_graph(&example());
+        let directions = get_directions("LLR");
+
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.get_id("BBB"), Some(1));
+        assert_eq!(graph.get_name(2), "ZZZ");
+        assert_eq!(graph.get_id("QQQ"), None);
+        assert_eq!(graph.compute_distance("AAA", "ZZZ", &directions), 6);
+    }
+
+    #[test]
+    fn jump_table_matches_walking() {
+        let lines: Vec<String> = vec![
+            String::from("11A = (11B, XXX)"),
+            String::from("11B = (XXX, 11Z)"),
+            String::from("11Z = (11B, XXX)"),
+            String::from("22A = (22B, XXX)"),
+            String::from("22B = (22C, 22C)"),
+            String::from("22C = (22Z, 22Z)"),
+            String::from("22Z = (22B, 22B)"),
+            String::from("XXX = (XXX, XXX)"),
+        ];
+        let graph = build_graph(&lines);
+        let directions = get_directions("LRR");
+        let table = JumpTable::new(&graph, &directions, 1000);
+
+        for start in 0..graph.len() as u32 {
+            let mut node = start;
+
+            for steps in 0..=1000_u64 {
+                assert_eq!(
+                    table.position_after(start, steps),
+                    node,
+                    "{} {}",
+                    start,
+                    steps
+                );
+                node = graph.next(node, &directions[steps as usize % directions.len()]);
+            }
         }
 
-        iterations_vec.iter().fold(1, |acc, &v| lcm(acc, v))
+        let huge = JumpTable::new(&graph, &directions, u64::MAX);
+        let start = graph.get_id("22A").unwrap();
+
+        // 22B -> 22C -> 22Z -> 22B every three steps once past the first
+        assert_eq!(huge.position_after(start, 3_000_000_000_001), start + 1);
+    }
+
+    #[test]
+    fn single_direction_covers_every_step_count() {
+        let graph = build_graph(&example());
+        let directions = get_directions("R");
+        let table = JumpTable::new(&graph, &directions, u64::MAX);
+        let start = graph.get_id("AAA").unwrap();
+        let end = graph.get_id("ZZZ").unwrap();
+
+        // One pass per step, so the table needs all 64 levels
+        assert_eq!(table.position_after(start, 1), graph.get_id("BBB").unwrap());
+        assert_eq!(table.position_after(start, u64::MAX), end);
     }
 
-    fn get(&self, name: &str) -> &NodePath {
-        self.nodes_to_paths.get(name).unwrap()
+    #[test]
+    #[should_panic(expected = "Node BBB is defined more than once")]
+    fn duplicate_nodes_are_rejected() {
+        let mut lines = example();
+        lines.push(String::from("BBB = (ZZZ, ZZZ)"));
+
+        build_graph(&lines);
     }
 }
@@ -1,71 +1,100 @@
+mod sequence;
+
+use crate::sequence::{Sequence, SequenceError};
 use common::filereader;
+use std::env;
 
-fn compute_differences(values: &Vec<i64>) -> Vec<i64> {
-    let mut differences = Vec::with_capacity(values.len() - 1);
+fn parse_sequence(line: &str) -> Sequence {
+    let values: Vec<i64> = line
+        .split_whitespace()
+        .map(|splitted| splitted.parse::<i64>().unwrap())
+        .collect();
 
-    for i in 0..(values.len() - 1) {
-        differences.push(values[i + 1] - values[i])
+    match Sequence::fit(&values) {
+        Ok(sequence) => sequence,
+        Err(e) => panic!("Can't extrapolate {}: {:?}", line, e),
     }
-
-    differences
 }
 
-fn get_extrapolated_value(values: Vec<i64>) -> i64 {
-    if values.iter().all(|&v| v == 0) {
-        return 0;
-    }
-
-    let differences = compute_differences(&values);
-    let &last_difference = values.last().unwrap();
-
-    let extrapolated = last_difference + get_extrapolated_value(differences);
-    println!("{:?}: {:?}", values, extrapolated);
-    return extrapolated;
+fn sum_extrapolated(
+    lines: Vec<String>,
+    extrapolate: fn(&Sequence) -> Result<i128, SequenceError>,
+) -> i128 {
+    lines
+        .iter()
+        .map(|line| parse_sequence(line))
+        .fold(0, |acc, sequence| match extrapolate(&sequence) {
+            Ok(value) => acc + value,
+            Err(e) => panic!("Can't extrapolate {:?}: {:?}", sequence, e),
+        })
 }
 
-fn get_historical_extrapolated_value(values: Vec<i64>) -> i64 {
-    if values.iter().all(|&v| v == 0) {
-        return 0;
-    }
-
-    let differences = compute_differences(&values);
-    let &first_difference = values.first().unwrap();
+fn solve(lines: Vec<String>) -> i128 {
+    sum_extrapolated(lines, Sequence::next_value)
+}
 
-    let extrapolated = first_difference - get_historical_extrapolated_value(differences);
-    println!("{:?}: {:?}", values, extrapolated);
-    return extrapolated;
+fn solve2(lines: Vec<String>) -> i128 {
+    sum_extrapolated(lines, Sequence::previous_value)
 }
 
-fn solve(lines: Vec<String>) -> i64 {
+// Every sequence evaluated at `index`, alongside the degree of the polynomial it fits
+fn evaluate_at(lines: &[String], index: i64) -> Vec<String> {
     lines
         .iter()
         .map(|line| {
-            line.split_whitespace()
-                .map(|splitted| splitted.parse::<i64>().unwrap())
-                .collect()
+            let sequence = parse_sequence(line);
+
+            match sequence.evaluate(index) {
+                Ok(value) => format!("{} (degree {})", value, sequence.get_degree()),
+                Err(e) => panic!("Can't evaluate {} at {}: {:?}", line, index, e),
+            }
         })
-        .fold(0, |acc, values| acc + get_extrapolated_value(values))
+        .collect()
 }
 
-fn solve2(lines: Vec<String>) -> i64 {
-    lines
-        .iter()
-        .map(|line| {
-            line.split_whitespace()
-                .map(|splitted| splitted.parse::<i64>().unwrap())
-                .collect()
-        })
-        .fold(0, |acc, values| {
-            acc + get_historical_extrapolated_value(values)
-        })
+// `--at N` prints each sequence's value at index N
+fn index_to_evaluate() -> Option<i64> {
+    let args: Vec<String> = env::args().collect();
+    let flag = args.iter().position(|arg| arg == "--at")?;
+
+    match args.get(flag + 1).map(|arg| arg.parse::<i64>()) {
+        Some(Ok(index)) => Some(index),
+        _ => panic!("--at needs an integer index"),
+    }
 }
 
 fn main() {
     match filereader::read_file("./day9/resources/input.txt") {
         Ok(lines) => {
+            if let Some(index) = index_to_evaluate() {
+                for line in evaluate_at(&lines, index) {
+                    println!("{}", line);
+                }
+            }
             let result = solve2(lines);
             println!("{:?}", result);
         }
         Err(e) => panic!("{}", e),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{evaluate_at, solve, solve2};
+
+    #[test]
+    fn example_report() {
+        let lines = vec![
+            String::from("0 3 6 9 12 15"),
+            String::from("1 3 6 10 15 21"),
+            String::from("10 13 16 21 30 45"),
+        ];
+
+        assert_eq!(
+            evaluate_at(&lines, 10),
+            vec!["30 (degree 1)", "66 (degree 2)", "280 (degree 3)"]
+        );
+        assert_eq!(solve(lines.clone()), 114);
+        assert_eq!(solve2(lines), 2);
+    }
+}
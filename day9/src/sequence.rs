@@ -0,0 +1,131 @@
+#[derive(Debug, PartialEq, Eq)]
+pub enum SequenceError {
+    Empty,
+    // No row, the readings included, came out all zero before the rows ran out
+    NeverSettles,
+    Overflow,
+}
+
+// The lowest-degree polynomial through the readings, stored in Newton form: p(x) is the
+// sum of leading_differences[k] * C(x, k), with the readings at x = 0, 1, 2, ...
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sequence {
+    leading_differences: Vec<i128>,
+    len: usize,
+}
+
+impl Sequence {
+    // A fit only counts once some row, starting with the readings themselves, is all zero,
+    // which needs at least one more reading than the polynomial's degree. So [7, 7] fits a
+    // constant and [0] fits zero, but [7] and [7, 8] have nothing to confirm them and
+    // never settle.
+    pub fn fit(values: &[i64]) -> Result<Self, SequenceError> {
+        if values.is_empty() {
+            return Err(SequenceError::Empty);
+        }
+
+        let mut row: Vec<i128> = values.iter().map(|&v| v as i128).collect();
+        let mut leading_differences = Vec::new();
+
+        while row.iter().any(|&v| v != 0) {
+            if row.len() == 1 {
+                return Err(SequenceError::NeverSettles);
+            }
+
+            leading_differences.push(row[0]);
+            row = row.windows(2).map(|w| w[1] - w[0]).collect();
+        }
+
+        Ok(Sequence {
+            leading_differences,
+            len: values.len(),
+        })
+    }
+
+    // Degree of the fitted polynomial, counting an all-zero sequence as degree 0
+    pub fn get_degree(&self) -> usize {
+        self.leading_differences.len().saturating_sub(1)
+    }
+
+    pub fn evaluate(&self, index: i64) -> Result<i128, SequenceError> {
+        let x = index as i128;
+        let mut binomial = 1_i128;
+        let mut total = 0_i128;
+
+        for (k, &difference) in self.leading_differences.iter().enumerate() {
+            if k > 0 {
+                // C(x, k) = C(x, k - 1) * (x - k + 1) / k, which always divides exactly
+                binomial = binomial
+                    .checked_mul(x - k as i128 + 1)
+                    .ok_or(SequenceError::Overflow)?
+                    / k as i128;
+            }
+
+            total = difference
+                .checked_mul(binomial)
+                .and_then(|term| total.checked_add(term))
+                .ok_or(SequenceError::Overflow)?;
+        }
+
+        Ok(total)
+    }
+
+    pub fn next_value(&self) -> Result<i128, SequenceError> {
+        self.evaluate(self.len as i64)
+    }
+
+    pub fn previous_value(&self) -> Result<i128, SequenceError> {
+        self.evaluate(-1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sequence::{Sequence, SequenceError};
+
+    #[test]
+    fn example_sequences() {
+        for (values, next, previous) in [
+            (vec![0, 3, 6, 9, 12, 15], 18, -3),
+            (vec![1, 3, 6, 10, 15, 21], 28, 0),
+            (vec![10, 13, 16, 21, 30, 45], 68, 5),
+        ] {
+            let sequence = Sequence::fit(&values).unwrap();
+
+            assert_eq!(sequence.next_value(), Ok(next));
+            assert_eq!(sequence.previous_value(), Ok(previous));
+        }
+    }
+
+    #[test]
+    fn evaluates_anywhere() {
+        // n^3 - 2n
+        let sequence = Sequence::fit(&[0, -1, 4, 21, 56]).unwrap();
+
+        assert_eq!(sequence.get_degree(), 3);
+        for n in [-50_i64, -7, 0, 3, 1_000, 1_000_000] {
+            let n128 = n as i128;
+            assert_eq!(sequence.evaluate(n), Ok(n128 * n128 * n128 - 2 * n128));
+        }
+
+        assert_eq!(Sequence::fit(&[0, 0, 0]).unwrap().evaluate(12345), Ok(0));
+        assert_eq!(Sequence::fit(&[7, 7]).unwrap().evaluate(-3), Ok(7));
+        assert_eq!(
+            Sequence::fit(&(0..10).map(|n: i64| n.pow(8)).collect::<Vec<_>>())
+                .unwrap()
+                .evaluate(i64::MAX),
+            Err(SequenceError::Overflow)
+        );
+    }
+
+    #[test]
+    fn reports_unsettled_sequences() {
+        assert_eq!(Sequence::fit(&[1, 2, 4]), Err(SequenceError::NeverSettles));
+        // A single non-zero reading has no difference row to confirm it, any more than two
+        // readings confirm a line
+        assert_eq!(Sequence::fit(&[7]), Err(SequenceError::NeverSettles));
+        assert_eq!(Sequence::fit(&[7, 8]), Err(SequenceError::NeverSettles));
+        assert_eq!(Sequence::fit(&[0]).unwrap().evaluate(5), Ok(0));
+        assert_eq!(Sequence::fit(&[]), Err(SequenceError::Empty));
+    }
+}